@@ -1,4 +1,5 @@
 use std::env;
+use std::io::ErrorKind;
 use std::path::Path;
 use std::process::Command;
 
@@ -11,16 +12,37 @@ fn main() {
     println!("cargo:rerun-if-changed=site/Cargo.toml");
     println!("cargo:rerun-if-changed=site/public");
     println!("cargo:rerun-if-changed=site/dist");
+    println!("cargo:rerun-if-env-changed=WATCHTOWER_SKIP_SITE_BUILD");
 
     if !leptos_site_dir.exists() {
         panic!("site directory not found at {:?}", leptos_site_dir);
     }
 
-    let status = Command::new("trunk")
+    if env::var_os("WATCHTOWER_SKIP_SITE_BUILD").is_some() {
+        if !leptos_site_dir.join("dist").exists() {
+            panic!(
+                "WATCHTOWER_SKIP_SITE_BUILD is set but {:?} does not exist; run `trunk build --release` in the site directory once first",
+                leptos_site_dir.join("dist")
+            );
+        }
+        println!("cargo:warning=WATCHTOWER_SKIP_SITE_BUILD is set; embedding the existing site/dist without rebuilding");
+        return;
+    }
+
+    let status = match Command::new("trunk")
         .args(["build", "--release"])
         .current_dir(&leptos_site_dir)
         .status()
-        .expect("Failed to run trunk build");
+    {
+        Ok(status) => status,
+        Err(error) if error.kind() == ErrorKind::NotFound => {
+            println!("cargo:warning=`trunk` was not found on PATH. Install it with `cargo install trunk` and add the wasm target with `rustup target add wasm32-unknown-unknown`.");
+            panic!(
+                "`trunk` is required to build the site. Run `cargo install trunk` and `rustup target add wasm32-unknown-unknown`, or set WATCHTOWER_SKIP_SITE_BUILD=1 to reuse an existing site/dist"
+            );
+        }
+        Err(error) => panic!("Failed to run trunk build: {error}"),
+    };
 
     if !status.success() {
         panic!("trunk build failed with status: {}", status);
@@ -5,13 +5,21 @@ use alloc::string::String;
 use alloc::vec::Vec;
 use serde::{Deserialize, Serialize};
 
+pub const PROTOCOL_VERSION: u32 = 2;
+
+pub const ASSEMBLE_DEMOS: &[&str] = &["cityscape", "solar_system", "garden", "abstract"];
+
 #[derive(Clone, Serialize, Deserialize)]
 pub enum FrontendCommand {
-    Ready,
+    Ready {
+        #[serde(default)]
+        protocol_version: u32,
+    },
     SendPrompt {
         prompt: String,
         session_id: Option<String>,
         model: Option<String>,
+        image_data_url: Option<String>,
     },
     CancelRequest,
     UserInputResponse {
@@ -21,12 +29,29 @@ pub enum FrontendCommand {
     RunTest {
         test_name: String,
     },
-    Assemble,
+    Assemble {
+        demo: Option<String>,
+        seed: Option<u64>,
+    },
+    SetConfig {
+        config: Config,
+    },
+    Undo,
+    Redo,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
 pub enum BackendEvent {
-    Connected,
+    Connected {
+        #[serde(default)]
+        mcp_port: u16,
+        #[serde(default)]
+        version: String,
+        #[serde(default)]
+        capabilities: Vec<String>,
+        #[serde(default)]
+        protocol_version: u32,
+    },
     StreamingStarted {
         session_id: String,
     },
@@ -47,6 +72,11 @@ pub enum BackendEvent {
     ToolUseFinished {
         tool_id: String,
     },
+    ToolUseResult {
+        tool_id: String,
+        content: String,
+        is_error: bool,
+    },
     TurnComplete {
         session_id: String,
     },
@@ -80,6 +110,25 @@ pub enum BackendEvent {
         message: String,
         duration_ms: u64,
     },
+    ToolError {
+        tool_name: String,
+        message: String,
+    },
+    ConfigChanged {
+        config: Config,
+    },
+    ChangeLog {
+        text: String,
+    },
+    HistoryChanged {
+        undo_depth: usize,
+        redo_depth: usize,
+    },
+    ProtocolMismatch {
+        backend_version: u32,
+        frontend_version: u32,
+    },
+    ReloadSite,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -95,4 +144,28 @@ pub enum ContentFormat {
     Markdown,
     Code,
     Text,
+    Table,
+    Diff,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub default_model: Option<String>,
+    pub cli_test_timeout_secs: u64,
+    pub shadows_enabled: bool,
+    pub keyboard_camera: bool,
+    pub preserve_camera_on_assemble: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            default_model: None,
+            cli_test_timeout_secs: 60,
+            shadows_enabled: true,
+            keyboard_camera: true,
+            preserve_camera_on_assemble: false,
+        }
+    }
 }
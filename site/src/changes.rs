@@ -0,0 +1,50 @@
+use leptos::prelude::*;
+
+use crate::state::AppState;
+
+#[component]
+pub fn ChangesTab(state: AppState) -> impl IntoView {
+    let change_log = state.change_log;
+
+    view! {
+        <div class="flex flex-col h-full">
+            <div class="px-4 py-3 border-b border-[#30363d] flex items-center justify-between">
+                <div>
+                    <h2 class="text-sm font-bold text-[#c9d1d9]">"Scene Changes"</h2>
+                    <p class="text-xs text-[#484f58] mt-0.5">"A running description of what the agent has done to the 3D scene"</p>
+                </div>
+                <button
+                    class="px-4 py-1.5 text-xs font-medium bg-[#21262d] text-[#c9d1d9] border border-[#30363d] rounded-md hover:bg-[#30363d] cursor-pointer"
+                    on:click=move |_| change_log.set(Vec::new())
+                >
+                    "Clear"
+                </button>
+            </div>
+            <div class="flex-1 overflow-y-auto px-4 py-4">
+                {move || {
+                    let entries = change_log.get();
+                    if entries.is_empty() {
+                        view! {
+                            <div class="flex items-center justify-center h-full text-[#484f58] text-sm">
+                                "No scene changes yet"
+                            </div>
+                        }.into_any()
+                    } else {
+                        view! {
+                            <ol class="space-y-1.5">
+                                {entries.into_iter().enumerate().map(|(index, text)| {
+                                    view! {
+                                        <li class="flex gap-3 text-sm">
+                                            <span class="text-[#484f58] text-xs w-8 text-right shrink-0 pt-0.5">{index + 1}</span>
+                                            <span class="text-[#c9d1d9]">{text}</span>
+                                        </li>
+                                    }
+                                }).collect_view()}
+                            </ol>
+                        }.into_any()
+                    }
+                }}
+            </div>
+        </div>
+    }
+}
@@ -1,17 +1,21 @@
 use leptos::prelude::*;
 use web_sys::wasm_bindgen::JsCast;
+use web_sys::wasm_bindgen::closure::Closure;
 
 use crate::message::MessageBubble;
 use crate::state::{AppState, ChatMessage, MessageRole, StatusDisplay};
 use crate::tool_use::ToolUseDisplay;
-use watchtower_protocol::FrontendCommand;
+use watchtower_protocol::{ContentFormat, FrontendCommand};
 
 #[component]
 pub fn ChatView(state: AppState) -> impl IntoView {
     let (input_text, set_input_text) = signal(String::new());
+    let (pending_image, set_pending_image) = signal(None::<String>);
     let messages = state.messages;
     let streaming_text = state.streaming_text;
+    let streaming_chunks = state.streaming_chunks;
     let thinking_text = state.thinking_text;
+    let thinking_segments = state.thinking_segments;
     let active_tools = state.active_tools;
     let status = state.status;
     let pending_input = state.pending_input_request;
@@ -32,14 +36,18 @@ pub fn ChatView(state: AppState) -> impl IntoView {
         if text.trim().is_empty() {
             return;
         }
+        let image = pending_image.get_untracked();
 
         state.messages.update(|msgs| {
             msgs.push(ChatMessage {
                 role: MessageRole::User,
                 content: text.clone(),
-                thinking: String::new(),
+                format: ContentFormat::Text,
+                thinking: Vec::new(),
                 thinking_duration_ms: 0,
                 tool_uses: Vec::new(),
+                image: image.clone(),
+                timestamp_ms: js_sys::Date::now(),
             });
         });
 
@@ -47,12 +55,49 @@ pub fn ChatView(state: AppState) -> impl IntoView {
             prompt: text,
             session_id: state.current_session_id.get_untracked(),
             model: None,
+            image_data_url: image,
         });
 
         set_input_text.set(String::new());
+        set_pending_image.set(None);
+    };
+
+    let on_paste = move |event: web_sys::ClipboardEvent| {
+        let Some(data) = event.clipboard_data() else {
+            return;
+        };
+        let items = data.items();
+        for index in 0..items.length() {
+            let Some(item) = items.get(index) else {
+                continue;
+            };
+            if !item.type_().starts_with("image/") {
+                continue;
+            }
+            let Ok(Some(file)) = item.get_as_file() else {
+                continue;
+            };
+            let Ok(reader) = web_sys::FileReader::new() else {
+                return;
+            };
+            event.prevent_default();
+
+            let loaded_reader = reader.clone();
+            let on_load = Closure::once_into_js(move || {
+                if let Ok(result) = loaded_reader.result()
+                    && let Some(data_url) = result.as_string()
+                {
+                    set_pending_image.set(Some(data_url));
+                }
+            });
+            reader.set_onload(Some(on_load.unchecked_ref()));
+            let _ = reader.read_as_data_url(&file);
+            return;
+        }
     };
 
     let cancel = move |_| {
+        pending_input.set(None);
         nightshade::webview::send(&FrontendCommand::CancelRequest);
     };
 
@@ -69,13 +114,31 @@ pub fn ChatView(state: AppState) -> impl IntoView {
         }
     };
 
+    let has_stream_text = Memo::new(move |_| {
+        !streaming_text.with(String::is_empty) || !streaming_chunks.with(Vec::is_empty)
+    });
+    let live_thinking = Memo::new(move |_| {
+        let mut thinking_parts = thinking_segments.get();
+        let current_thinking = thinking_text.get();
+        if !current_thinking.is_empty() {
+            thinking_parts.push(current_thinking);
+        }
+        thinking_parts.join("\n\n")
+    });
+    let is_thinking = Memo::new(move |_| matches!(status.get(), StatusDisplay::Thinking));
+    let is_streaming_active = Memo::new(move |_| {
+        has_stream_text.get()
+            || !active_tools.with(Vec::is_empty)
+            || !live_thinking.with(String::is_empty)
+            || is_thinking.get()
+    });
+
     view! {
         <div class="flex flex-col h-full">
             <div class="flex-1 overflow-y-auto px-4 py-4" id="chat-scroll-container">
                 {move || {
                     let msgs = messages.get();
-                    let is_thinking = matches!(status.get(), StatusDisplay::Thinking);
-                    if msgs.is_empty() && streaming_text.get().is_empty() && thinking_text.get().is_empty() && !is_thinking {
+                    if msgs.is_empty() && !is_streaming_active.get() {
                         view! {
                             <div class="flex items-center justify-center h-full text-[#484f58] text-sm">
                                 "Send a prompt to get started"
@@ -88,64 +151,59 @@ pub fn ChatView(state: AppState) -> impl IntoView {
                                     view! { <MessageBubble message=message /> }
                                 }).collect_view()}
 
-                                {move || {
-                                    let thinking = thinking_text.get();
-                                    let text = streaming_text.get();
-                                    let tools = active_tools.get();
-                                    let current_status = status.get();
-                                    let is_thinking = matches!(current_status, StatusDisplay::Thinking);
-                                    let is_active = !text.is_empty() || !tools.is_empty() || !thinking.is_empty() || is_thinking;
-
-                                    if is_active {
-                                        Some(view! {
-                                            <div class="flex justify-start mb-3">
-                                                <div class="max-w-[80%] px-4 py-2.5 rounded-lg bg-[#161b22] text-[#c9d1d9] border border-[#30363d]">
-                                                    {if !thinking.is_empty() {
-                                                        view! {
-                                                            <div class="mb-3 pb-3 border-b border-[#30363d]">
-                                                                <div class="flex items-center gap-1.5 mb-1">
-                                                                    <span class="text-yellow-500 text-xs">"Thinking"</span>
-                                                                </div>
-                                                                <pre class="whitespace-pre-wrap break-words font-mono text-xs leading-relaxed m-0 text-[#8b949e]">{thinking}</pre>
+                                {move || is_streaming_active.get().then(|| view! {
+                                    <div class="flex justify-start mb-3">
+                                        <div class="max-w-[80%] px-4 py-2.5 rounded-lg bg-[#161b22] text-[#c9d1d9] border border-[#30363d]">
+                                            {move || {
+                                                let thinking = live_thinking.get();
+                                                if !thinking.is_empty() {
+                                                    view! {
+                                                        <div class="mb-3 pb-3 border-b border-[#30363d]">
+                                                            <div class="flex items-center gap-1.5 mb-1">
+                                                                <span class="text-yellow-500 text-xs">"Thinking"</span>
                                                             </div>
-                                                        }.into_any()
-                                                    } else if is_thinking && text.is_empty() {
-                                                        view! {
-                                                            <div class="mb-3 pb-3 border-b border-[#30363d]">
-                                                                <div class="flex items-center gap-1.5">
-                                                                    <span class="text-yellow-500 text-xs animate-pulse">"Thinking..."</span>
-                                                                </div>
+                                                            <pre class="whitespace-pre-wrap break-words font-mono text-xs leading-relaxed m-0 text-[#8b949e]">{thinking}</pre>
+                                                        </div>
+                                                    }.into_any()
+                                                } else if is_thinking.get() && !has_stream_text.get() {
+                                                    view! {
+                                                        <div class="mb-3 pb-3 border-b border-[#30363d]">
+                                                            <div class="flex items-center gap-1.5">
+                                                                <span class="text-yellow-500 text-xs animate-pulse">"Thinking..."</span>
                                                             </div>
-                                                        }.into_any()
-                                                    } else {
-                                                        view! { <div></div> }.into_any()
-                                                    }}
-                                                    {if !text.is_empty() {
-                                                        Some(view! {
-                                                            <pre class="whitespace-pre-wrap break-words font-mono text-sm leading-relaxed m-0">{text}</pre>
-                                                        })
-                                                    } else {
-                                                        None
-                                                    }}
-                                                    {if !tools.is_empty() {
-                                                        Some(view! {
-                                                            <div class="mt-2">
-                                                                {tools.into_iter().map(|tool| {
-                                                                    view! { <ToolUseDisplay tool=tool /> }
-                                                                }).collect_view()}
-                                                            </div>
-                                                        })
-                                                    } else {
-                                                        None
-                                                    }}
-                                                    <span class="inline-block w-2 h-4 bg-[#c9d1d9] animate-pulse ml-0.5"></span>
-                                                </div>
-                                            </div>
-                                        })
-                                    } else {
-                                        None
-                                    }
-                                }}
+                                                        </div>
+                                                    }.into_any()
+                                                } else {
+                                                    view! { <div></div> }.into_any()
+                                                }
+                                            }}
+                                            <For
+                                                each=move || streaming_chunks.get().into_iter().enumerate()
+                                                key=|(index, _)| *index
+                                                children=|(_, chunk)| view! {
+                                                    <pre class="whitespace-pre-wrap break-words font-mono text-sm leading-relaxed m-0">{chunk}</pre>
+                                                }
+                                            />
+                                            {move || {
+                                                let text = streaming_text.get();
+                                                (!text.is_empty()).then(|| view! {
+                                                    <pre class="whitespace-pre-wrap break-words font-mono text-sm leading-relaxed m-0">{text}</pre>
+                                                })
+                                            }}
+                                            {move || {
+                                                let tools = active_tools.get();
+                                                (!tools.is_empty()).then(|| view! {
+                                                    <div class="mt-2">
+                                                        {tools.into_iter().map(|tool| {
+                                                            view! { <ToolUseDisplay tool=tool /> }
+                                                        }).collect_view()}
+                                                    </div>
+                                                })
+                                            }}
+                                            <span class="inline-block w-2 h-4 bg-[#c9d1d9] animate-pulse ml-0.5"></span>
+                                        </div>
+                                    </div>
+                                })}
                             </div>
                         }.into_any()
                     }
@@ -187,10 +245,21 @@ pub fn ChatView(state: AppState) -> impl IntoView {
             }}
 
             <div class="px-4 py-3 bg-[#161b22] border-t border-[#30363d]">
+                {move || pending_image.get().map(|data_url| view! {
+                    <div class="flex items-start gap-2 mb-2">
+                        <img src=data_url class="max-h-20 rounded border border-[#30363d]" />
+                        <button
+                            class="text-[#484f58] hover:text-[#c9d1d9] text-xs cursor-pointer"
+                            on:click=move |_| set_pending_image.set(None)
+                        >
+                            "✕"
+                        </button>
+                    </div>
+                })}
                 <div class="flex gap-2">
                     <textarea
                         class="flex-1 bg-[#0d1117] text-[#c9d1d9] border border-[#30363d] rounded-lg px-3 py-2 text-sm font-mono resize-none focus:outline-none focus:border-[#58a6ff] placeholder-[#484f58]"
-                        placeholder="Type a prompt... (Ctrl+Enter to send)"
+                        placeholder="Type a prompt... (Ctrl+Enter to send, paste an image to attach it)"
                         rows="3"
                         prop:value=move || input_text.get()
                         on:input=move |event| {
@@ -199,6 +268,7 @@ pub fn ChatView(state: AppState) -> impl IntoView {
                             set_input_text.set(textarea.value());
                         }
                         on:keydown=on_keydown
+                        on:paste=on_paste
                     />
                     <div class="flex flex-col gap-1">
                         <button
@@ -211,7 +281,7 @@ pub fn ChatView(state: AppState) -> impl IntoView {
                         <button
                             class="px-4 py-2 bg-[#da3633] text-white text-sm rounded-lg hover:bg-[#f85149] disabled:opacity-40 disabled:cursor-not-allowed cursor-pointer"
                             on:click=cancel
-                            disabled=move || !is_busy()
+                            disabled=move || !is_busy() && pending_input.get().is_none()
                         >
                             "Cancel"
                         </button>
@@ -0,0 +1,106 @@
+pub struct Table {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum DiffLineKind {
+    Added,
+    Removed,
+    Hunk,
+    Context,
+}
+
+pub fn parse_table(content: &str) -> Option<Table> {
+    let trimmed = content.trim();
+    if trimmed.starts_with('[') {
+        parse_json_table(trimmed)
+    } else {
+        parse_csv_table(trimmed)
+    }
+}
+
+fn parse_json_table(content: &str) -> Option<Table> {
+    let value: serde_json::Value = serde_json::from_str(content).ok()?;
+    let items = value.as_array()?;
+    let first = items.first()?;
+
+    if let Some(object) = first.as_object() {
+        let headers: Vec<String> = object.keys().cloned().collect();
+        let rows = items
+            .iter()
+            .map(|item| {
+                headers
+                    .iter()
+                    .map(|header| item.get(header).map(json_cell).unwrap_or_default())
+                    .collect()
+            })
+            .collect();
+        return Some(Table { headers, rows });
+    }
+
+    let mut rows: Vec<Vec<String>> = items
+        .iter()
+        .map(|item| {
+            item.as_array()
+                .map(|cells| cells.iter().map(json_cell).collect())
+                .unwrap_or_else(|| vec![json_cell(item)])
+        })
+        .collect();
+    let headers = rows.remove(0);
+    Some(Table { headers, rows })
+}
+
+fn json_cell(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(text) => text.clone(),
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn parse_csv_table(content: &str) -> Option<Table> {
+    let mut lines = content.lines().filter(|line| !line.trim().is_empty());
+    let headers = parse_csv_line(lines.next()?);
+    if headers.len() < 2 && !content.contains(',') {
+        return None;
+    }
+    let rows = lines.map(parse_csv_line).collect();
+    Some(Table { headers, rows })
+}
+
+fn parse_csv_line(line: &str) -> Vec<String> {
+    let mut cells = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut characters = line.chars().peekable();
+
+    while let Some(character) = characters.next() {
+        match character {
+            '"' if in_quotes && characters.peek() == Some(&'"') => {
+                current.push('"');
+                characters.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => {
+                cells.push(current.trim().to_string());
+                current.clear();
+            }
+            _ => current.push(character),
+        }
+    }
+    cells.push(current.trim().to_string());
+    cells
+}
+
+pub fn classify_diff_line(line: &str) -> DiffLineKind {
+    if line.starts_with("+++") || line.starts_with("---") || line.starts_with("@@") {
+        DiffLineKind::Hunk
+    } else if line.starts_with('+') {
+        DiffLineKind::Added
+    } else if line.starts_with('-') {
+        DiffLineKind::Removed
+    } else {
+        DiffLineKind::Context
+    }
+}
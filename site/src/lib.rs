@@ -1,15 +1,21 @@
+mod changes;
 mod chat;
+mod content;
 mod message;
+mod settings;
 mod state;
 mod test_tab;
 mod toolbar;
 mod tool_use;
+mod transcript;
 
 use leptos::prelude::*;
-use watchtower_protocol::{BackendEvent, ContentFormat, FrontendCommand};
+use watchtower_protocol::{BackendEvent, ContentFormat, FrontendCommand, PROTOCOL_VERSION};
 
+use crate::changes::ChangesTab;
 use crate::chat::ChatView;
-use crate::state::{ActiveTab, AppState, ChatMessage, InputRequest, MessageRole, StatusDisplay, TestEntry, TestStatus, ToolUseBlock};
+use crate::settings::SettingsTab;
+use crate::state::{ActiveTab, AppState, ChatMessage, InputRequest, MAX_CHANGE_LOG_ENTRIES, MessageRole, ServerInfo, StatusDisplay, TestEntry, TestStatus, ToolUseBlock};
 use crate::test_tab::TestTab;
 use crate::toolbar::Toolbar;
 
@@ -20,14 +26,23 @@ pub fn App() -> impl IntoView {
     let state_for_handler = state.clone();
     Effect::new(move |_| {
         let handler_state = state_for_handler.clone();
-        nightshade::webview::connect(FrontendCommand::Ready, move |event| {
+        nightshade::webview::connect(FrontendCommand::Ready { protocol_version: PROTOCOL_VERSION }, move |event| {
             handle_backend_event(&handler_state, event);
         });
     });
 
+    let _reload_shortcut = window_event_listener(leptos::ev::keydown, |event| {
+        if event.key() == "F5" {
+            event.prevent_default();
+            reload_site();
+        }
+    });
+
     let toolbar_state = state.clone();
     let chat_state = state.clone();
     let test_state = state.clone();
+    let changes_state = state.clone();
+    let settings_state = state.clone();
     let active_tab = state.active_tab;
     let notifications_state = state.clone();
 
@@ -39,6 +54,8 @@ pub fn App() -> impl IntoView {
                 {move || match active_tab.get() {
                     ActiveTab::Chat => view! { <ChatView state=chat_state.clone() /> }.into_any(),
                     ActiveTab::Test => view! { <TestTab state=test_state.clone() /> }.into_any(),
+                    ActiveTab::Changes => view! { <ChangesTab state=changes_state.clone() /> }.into_any(),
+                    ActiveTab::Settings => view! { <SettingsTab state=settings_state.clone() /> }.into_any(),
                 }}
             </div>
 
@@ -84,24 +101,35 @@ pub fn App() -> impl IntoView {
 
 fn handle_backend_event(state: &AppState, event: BackendEvent) {
     match event {
-        BackendEvent::Connected => {
+        BackendEvent::Connected { mcp_port, version, capabilities, protocol_version } => {
             state.connected.set(true);
+            state.server_info.set(Some(ServerInfo {
+                mcp_port,
+                version,
+                capabilities,
+                protocol_version,
+            }));
             state.status.set(StatusDisplay::Idle);
         }
 
         BackendEvent::StreamingStarted { session_id } => {
+            let previous_session_id = state.current_session_id.get_untracked();
+            if previous_session_id.as_deref() != Some(session_id.as_str()) {
+                state.push_system_message(format!("New session {session_id}"));
+                state.session_totals.set(Default::default());
+            }
             state.current_session_id.set(Some(session_id));
-            state.streaming_text.set(String::new());
-            state.thinking_text.set(String::new());
+            state.clear_streaming_text();
+            state.end_thinking_turn();
             state.active_tools.set(Vec::new());
         }
 
         BackendEvent::TextDelta { text } => {
-            state.streaming_text.update(|current| current.push_str(&text));
+            state.push_text_delta(&text);
         }
 
         BackendEvent::ThinkingDelta { text } => {
-            state.thinking_text.update(|current| current.push_str(&text));
+            state.push_thinking_delta(&text);
         }
 
         BackendEvent::ToolUseStarted { tool_name, tool_id } => {
@@ -111,6 +139,9 @@ fn handle_backend_event(state: &AppState, event: BackendEvent) {
                     tool_id,
                     input_json: String::new(),
                     finished: false,
+                    cancelled: false,
+                    result: None,
+                    is_error: false,
                 });
             });
         }
@@ -131,23 +162,47 @@ fn handle_backend_event(state: &AppState, event: BackendEvent) {
             });
         }
 
-        BackendEvent::TurnComplete { .. } => {}
+        BackendEvent::ToolUseResult { tool_id, content, is_error } => {
+            let mut found = false;
+            state.active_tools.update(|tools| {
+                if let Some(tool) = tools.iter_mut().find(|t| t.tool_id == tool_id) {
+                    tool.finished = true;
+                    tool.result = Some(content.clone());
+                    tool.is_error = is_error;
+                    found = true;
+                }
+            });
+            if !found {
+                state.messages.update(|messages| {
+                    let tool = messages
+                        .iter_mut()
+                        .rev()
+                        .flat_map(|message| message.tool_uses.iter_mut())
+                        .find(|t| t.tool_id == tool_id);
+                    if let Some(tool) = tool {
+                        tool.result = Some(content);
+                        tool.is_error = is_error;
+                    }
+                });
+            }
+        }
+
+        BackendEvent::TurnComplete { .. } => {
+            state.end_thinking_turn();
+        }
 
-        BackendEvent::RequestComplete { .. } => {
+        BackendEvent::RequestComplete { total_cost_usd, num_turns, .. } => {
+            state.session_totals.update(|totals| {
+                totals.cost_usd += total_cost_usd.unwrap_or(0.0);
+                totals.num_turns += num_turns;
+                totals.requests += 1;
+            });
             state.finalize_streaming_message();
         }
 
         BackendEvent::Error { message } => {
             state.finalize_streaming_message();
-            state.messages.update(|messages| {
-                messages.push(ChatMessage {
-                    role: MessageRole::Assistant,
-                    content: format!("Error: {message}"),
-                    thinking: String::new(),
-                    thinking_duration_ms: 0,
-                    tool_uses: Vec::new(),
-                });
-            });
+            state.push_system_message(format!("Error: {message}"));
         }
 
         BackendEvent::StatusUpdate { status } => {
@@ -164,18 +219,16 @@ fn handle_backend_event(state: &AppState, event: BackendEvent) {
         }
 
         BackendEvent::ContentDisplay { content, format } => {
-            let prefix = match format {
-                ContentFormat::Code => "[Code]\n",
-                ContentFormat::Markdown => "[Markdown]\n",
-                ContentFormat::Text => "",
-            };
             state.messages.update(|messages| {
                 messages.push(ChatMessage {
                     role: MessageRole::Assistant,
-                    content: format!("{prefix}{content}"),
-                    thinking: String::new(),
+                    content,
+                    format,
+                    thinking: Vec::new(),
                     thinking_duration_ms: 0,
                     tool_uses: Vec::new(),
+                    image: None,
+                    timestamp_ms: js_sys::Date::now(),
                 });
             });
         }
@@ -188,6 +241,48 @@ fn handle_backend_event(state: &AppState, event: BackendEvent) {
             }));
         }
 
+        BackendEvent::ToolError { tool_name, message } => {
+            state.messages.update(|messages| {
+                messages.push(ChatMessage {
+                    role: MessageRole::ToolError { tool_name },
+                    content: message,
+                    format: ContentFormat::Text,
+                    thinking: Vec::new(),
+                    thinking_duration_ms: 0,
+                    tool_uses: Vec::new(),
+                    image: None,
+                    timestamp_ms: js_sys::Date::now(),
+                });
+            });
+        }
+
+        BackendEvent::HistoryChanged { undo_depth, redo_depth } => {
+            state.history_depth.set((undo_depth, redo_depth));
+        }
+
+        BackendEvent::ProtocolMismatch { backend_version, frontend_version } => {
+            state.push_system_message(format!(
+                "Protocol mismatch: the UI speaks version {frontend_version} but the backend speaks version {backend_version}. Rebuild the site or reload the page."
+            ));
+        }
+
+        BackendEvent::ChangeLog { text } => {
+            state.change_log.update(|entries| {
+                if entries.len() == MAX_CHANGE_LOG_ENTRIES {
+                    entries.remove(0);
+                }
+                entries.push(text);
+            });
+        }
+
+        BackendEvent::ConfigChanged { config } => {
+            state.config.set(config);
+        }
+
+        BackendEvent::ReloadSite => {
+            reload_site();
+        }
+
         BackendEvent::TestResult { test_name, success, message, duration_ms } => {
             state.test_results.update(|results| {
                 if let Some(entry) = results.iter_mut().find(|entry| entry.test_name == test_name) {
@@ -206,3 +301,7 @@ fn handle_backend_event(state: &AppState, event: BackendEvent) {
         }
     }
 }
+
+fn reload_site() {
+    let _ = window().location().reload();
+}
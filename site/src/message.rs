@@ -1,16 +1,47 @@
 use leptos::prelude::*;
+use watchtower_protocol::ContentFormat;
 
+use crate::content::{DiffLineKind, classify_diff_line, parse_table};
 use crate::state::{ChatMessage, MessageRole};
 use crate::tool_use::ToolUseDisplay;
 
 #[component]
 pub fn MessageBubble(message: ChatMessage) -> impl IntoView {
+    if matches!(message.role, MessageRole::System) {
+        return view! {
+            <div class="flex items-center gap-3 my-3 text-xs text-[#484f58]">
+                <div class="flex-1 border-t border-[#21262d]"></div>
+                <span class="whitespace-pre-wrap text-center">{message.content}</span>
+                <div class="flex-1 border-t border-[#21262d]"></div>
+            </div>
+        }.into_any();
+    }
+
+    if let MessageRole::ToolError { tool_name } = &message.role {
+        let tool_name = tool_name.clone();
+        return view! {
+            <div class="flex justify-start mb-3">
+                <div class="max-w-[80%] px-4 py-2.5 rounded-lg bg-[#2d1117] border border-[#f85149] text-[#ffa198]">
+                    <div class="flex items-center gap-1.5 mb-1 text-xs">
+                        <span class="text-[#f85149]">"✗"</span>
+                        <span class="font-medium text-[#f85149]">{tool_name}</span>
+                        <span class="text-[#8b949e]">"failed"</span>
+                    </div>
+                    <pre class="whitespace-pre-wrap break-words font-mono text-sm leading-relaxed m-0">{message.content}</pre>
+                </div>
+            </div>
+        }.into_any();
+    }
+
     let is_user = matches!(message.role, MessageRole::User);
     let content = message.content.clone();
+    let format = message.format.clone();
     let thinking = message.thinking.clone();
     let thinking_duration_ms = message.thinking_duration_ms;
     let has_thinking = !thinking.is_empty() || thinking_duration_ms > 0;
     let tool_uses = message.tool_uses.clone();
+    let image = message.image.clone();
+    let copy_text = message.content.clone();
     let (thinking_expanded, set_thinking_expanded) = signal(false);
 
     let container_class = if is_user {
@@ -53,9 +84,13 @@ pub fn MessageBubble(message: ChatMessage) -> impl IntoView {
                                 if thinking_expanded.get() {
                                     if has_thinking_text {
                                         view! {
-                                            <pre class="whitespace-pre-wrap break-words font-mono text-xs leading-relaxed mt-1 text-[#8b949e] pl-3 border-l-2 border-[#30363d]">
-                                                {thinking_clone.clone()}
-                                            </pre>
+                                            <div>
+                                                {thinking_clone.clone().into_iter().map(|segment| view! {
+                                                    <pre class="whitespace-pre-wrap break-words font-mono text-xs leading-relaxed mt-1 text-[#8b949e] pl-3 border-l-2 border-[#30363d]">
+                                                        {segment}
+                                                    </pre>
+                                                }).collect_view()}
+                                            </div>
                                         }.into_any()
                                     } else {
                                         view! {
@@ -73,7 +108,18 @@ pub fn MessageBubble(message: ChatMessage) -> impl IntoView {
                 } else {
                     None
                 }}
-                <pre class="whitespace-pre-wrap break-words font-mono text-sm leading-relaxed m-0">{content}</pre>
+                {image.map(|data_url| view! {
+                    <img src=data_url class="max-h-40 rounded mb-2" />
+                })}
+                <MessageContent content=content format=format />
+                {(!is_user && !copy_text.is_empty()).then(|| view! {
+                    <button
+                        class="mt-1 text-xs text-[#484f58] hover:text-[#8b949e] cursor-pointer bg-transparent"
+                        on:click=move |_| copy_to_clipboard(&copy_text)
+                    >
+                        "Copy"
+                    </button>
+                })}
                 {if !tool_uses.is_empty() {
                     Some(view! {
                         <div class="mt-2">
@@ -87,5 +133,69 @@ pub fn MessageBubble(message: ChatMessage) -> impl IntoView {
                 }}
             </div>
         </div>
+    }.into_any()
+}
+
+fn copy_to_clipboard(text: &str) {
+    if let Some(window) = web_sys::window() {
+        let _ = window.navigator().clipboard().write_text(text);
+    }
+}
+
+#[component]
+fn MessageContent(content: String, format: ContentFormat) -> impl IntoView {
+    match format {
+        ContentFormat::Table => match parse_table(&content) {
+            Some(table) => view! {
+                <div class="overflow-x-auto">
+                    <table class="text-xs font-mono border-collapse">
+                        <thead>
+                            <tr>
+                                {table.headers.into_iter().map(|header| view! {
+                                    <th class="px-2 py-1 text-left text-[#c9d1d9] border-b border-[#30363d]">{header}</th>
+                                }).collect_view()}
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {table.rows.into_iter().map(|row| view! {
+                                <tr class="border-b border-[#21262d]">
+                                    {row.into_iter().map(|cell| view! {
+                                        <td class="px-2 py-1 text-[#8b949e]">{cell}</td>
+                                    }).collect_view()}
+                                </tr>
+                            }).collect_view()}
+                        </tbody>
+                    </table>
+                </div>
+            }.into_any(),
+            None => view! {
+                <pre class="whitespace-pre-wrap break-words font-mono text-sm leading-relaxed m-0">{content}</pre>
+            }.into_any(),
+        },
+        ContentFormat::Diff => view! {
+            <pre class="font-mono text-xs leading-relaxed m-0 overflow-x-auto">
+                {content.lines().map(|line| {
+                    let line_class = match classify_diff_line(line) {
+                        DiffLineKind::Added => "block text-green-400 bg-green-900/30",
+                        DiffLineKind::Removed => "block text-red-400 bg-red-900/30",
+                        DiffLineKind::Hunk => "block text-[#58a6ff]",
+                        DiffLineKind::Context => "block text-[#8b949e]",
+                    };
+                    view! { <span class=line_class>{line.to_string()}</span> }
+                }).collect_view()}
+            </pre>
+        }.into_any(),
+        ContentFormat::Code | ContentFormat::Markdown => {
+            let label = if matches!(format, ContentFormat::Code) { "Code" } else { "Markdown" };
+            view! {
+                <div>
+                    <div class="text-xs text-[#484f58] mb-1">{label}</div>
+                    <pre class="whitespace-pre-wrap break-words font-mono text-sm leading-relaxed m-0">{content}</pre>
+                </div>
+            }.into_any()
+        }
+        ContentFormat::Text => view! {
+            <pre class="whitespace-pre-wrap break-words font-mono text-sm leading-relaxed m-0">{content}</pre>
+        }.into_any(),
     }
 }
@@ -0,0 +1,130 @@
+use leptos::prelude::*;
+use web_sys::wasm_bindgen::JsCast;
+use watchtower_protocol::{Config, FrontendCommand};
+
+use crate::state::AppState;
+
+#[component]
+pub fn SettingsTab(state: AppState) -> impl IntoView {
+    let config = state.config;
+    let (model, set_model) = signal(String::new());
+    let (timeout_secs, set_timeout_secs) = signal(String::new());
+    let (shadows_enabled, set_shadows_enabled) = signal(true);
+    let (keyboard_camera, set_keyboard_camera) = signal(true);
+    let (preserve_camera, set_preserve_camera) = signal(false);
+
+    Effect::new(move |_| {
+        let current = config.get();
+        set_model.set(current.default_model.unwrap_or_default());
+        set_timeout_secs.set(current.cli_test_timeout_secs.to_string());
+        set_shadows_enabled.set(current.shadows_enabled);
+        set_keyboard_camera.set(current.keyboard_camera);
+        set_preserve_camera.set(current.preserve_camera_on_assemble);
+    });
+
+    let on_save = move |_| {
+        let model = model.get_untracked();
+        let defaults = Config::default();
+        nightshade::webview::send(&FrontendCommand::SetConfig {
+            config: Config {
+                default_model: if model.trim().is_empty() { None } else { Some(model.trim().to_string()) },
+                cli_test_timeout_secs: timeout_secs
+                    .get_untracked()
+                    .trim()
+                    .parse()
+                    .unwrap_or(defaults.cli_test_timeout_secs),
+                shadows_enabled: shadows_enabled.get_untracked(),
+                keyboard_camera: keyboard_camera.get_untracked(),
+                preserve_camera_on_assemble: preserve_camera.get_untracked(),
+            },
+        });
+    };
+
+    view! {
+        <div class="flex flex-col h-full">
+            <div class="px-4 py-3 border-b border-[#30363d] flex items-center justify-between">
+                <div>
+                    <h2 class="text-sm font-bold text-[#c9d1d9]">"Settings"</h2>
+                    <p class="text-xs text-[#484f58] mt-0.5">"Preferences are saved to watchtower_config.json. WATCHTOWER_* environment variables override them for this session without being saved"</p>
+                </div>
+                <button
+                    class="px-4 py-1.5 text-xs font-medium bg-[#238636] text-white rounded-md hover:bg-[#2ea043] cursor-pointer"
+                    on:click=on_save
+                >
+                    "Save"
+                </button>
+            </div>
+            <div class="flex-1 overflow-y-auto px-4 py-4 space-y-3">
+                <div class="bg-[#161b22] border border-[#30363d] rounded-lg p-4">
+                    <h3 class="text-sm font-bold text-[#c9d1d9]">"Default Model"</h3>
+                    <p class="text-xs text-[#484f58] mt-1">"Used for prompts when no model is specified. Leave empty for the CLI default."</p>
+                    <input
+                        class="mt-2 w-full bg-[#0d1117] text-[#c9d1d9] border border-[#30363d] rounded px-3 py-1.5 text-xs font-mono focus:outline-none focus:border-[#58a6ff] placeholder-[#484f58]"
+                        placeholder="e.g. sonnet"
+                        prop:value=move || model.get()
+                        on:input=move |event| {
+                            let input: web_sys::HtmlInputElement = event.target().unwrap().unchecked_into();
+                            set_model.set(input.value());
+                        }
+                    />
+                </div>
+                <div class="bg-[#161b22] border border-[#30363d] rounded-lg p-4">
+                    <h3 class="text-sm font-bold text-[#c9d1d9]">"CLI Test Timeout"</h3>
+                    <p class="text-xs text-[#484f58] mt-1">"Seconds the CLI Prompt test waits for a response before failing."</p>
+                    <input
+                        type="number"
+                        min="1"
+                        class="mt-2 w-32 bg-[#0d1117] text-[#c9d1d9] border border-[#30363d] rounded px-3 py-1.5 text-xs font-mono focus:outline-none focus:border-[#58a6ff]"
+                        prop:value=move || timeout_secs.get()
+                        on:input=move |event| {
+                            let input: web_sys::HtmlInputElement = event.target().unwrap().unchecked_into();
+                            set_timeout_secs.set(input.value());
+                        }
+                    />
+                </div>
+                <div class="bg-[#161b22] border border-[#30363d] rounded-lg p-4">
+                    <label class="flex items-center gap-2 cursor-pointer">
+                        <input
+                            type="checkbox"
+                            prop:checked=move || shadows_enabled.get()
+                            on:change=move |event| {
+                                let input: web_sys::HtmlInputElement = event.target().unwrap().unchecked_into();
+                                set_shadows_enabled.set(input.checked());
+                            }
+                        />
+                        <h3 class="text-sm font-bold text-[#c9d1d9]">"Shadows"</h3>
+                    </label>
+                    <p class="text-xs text-[#484f58] mt-1">"Whether the sun casts shadows in the 3D scene."</p>
+                </div>
+                <div class="bg-[#161b22] border border-[#30363d] rounded-lg p-4">
+                    <label class="flex items-center gap-2 cursor-pointer">
+                        <input
+                            type="checkbox"
+                            prop:checked=move || keyboard_camera.get()
+                            on:change=move |event| {
+                                let input: web_sys::HtmlInputElement = event.target().unwrap().unchecked_into();
+                                set_keyboard_camera.set(input.checked());
+                            }
+                        />
+                        <h3 class="text-sm font-bold text-[#c9d1d9]">"Keyboard Camera"</h3>
+                    </label>
+                    <p class="text-xs text-[#484f58] mt-1">"WASD pans and Q/E zooms the camera while the 3D window has focus."</p>
+                </div>
+                <div class="bg-[#161b22] border border-[#30363d] rounded-lg p-4">
+                    <label class="flex items-center gap-2 cursor-pointer">
+                        <input
+                            type="checkbox"
+                            prop:checked=move || preserve_camera.get()
+                            on:change=move |event| {
+                                let input: web_sys::HtmlInputElement = event.target().unwrap().unchecked_into();
+                                set_preserve_camera.set(input.checked());
+                            }
+                        />
+                        <h3 class="text-sm font-bold text-[#c9d1d9]">"Preserve Camera on Assemble"</h3>
+                    </label>
+                    <p class="text-xs text-[#484f58] mt-1">"Keep your camera position when assembling a demo, if you moved it since the last one was framed."</p>
+                </div>
+            </div>
+        </div>
+    }
+}
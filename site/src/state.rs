@@ -1,10 +1,17 @@
 use leptos::prelude::*;
-use watchtower_protocol::AgentStatus;
+use watchtower_protocol::{AgentStatus, Config, ContentFormat};
+
+pub const MAX_LIVE_THINKING_BYTES: usize = 64 * 1024;
+const THINKING_TRUNCATED_PREFIX: &str = "…(truncated)…\n";
+const MAX_STREAMING_TAIL_BYTES: usize = 4 * 1024;
+pub const MAX_CHANGE_LOG_ENTRIES: usize = 500;
 
 #[derive(Clone, Copy, PartialEq)]
 pub enum ActiveTab {
     Chat,
     Test,
+    Changes,
+    Settings,
 }
 
 #[derive(Clone, PartialEq)]
@@ -29,21 +36,50 @@ pub struct ToolUseBlock {
     pub tool_id: String,
     pub input_json: String,
     pub finished: bool,
+    pub cancelled: bool,
+    pub result: Option<String>,
+    pub is_error: bool,
 }
 
 #[derive(Clone)]
 pub enum MessageRole {
     User,
     Assistant,
+    System,
+    ToolError { tool_name: String },
 }
 
 #[derive(Clone)]
 pub struct ChatMessage {
     pub role: MessageRole,
     pub content: String,
-    pub thinking: String,
+    pub format: ContentFormat,
+    pub thinking: Vec<String>,
     pub thinking_duration_ms: u64,
     pub tool_uses: Vec<ToolUseBlock>,
+    pub image: Option<String>,
+    pub timestamp_ms: f64,
+}
+
+#[derive(Clone)]
+pub struct ServerInfo {
+    pub mcp_port: u16,
+    pub version: String,
+    pub capabilities: Vec<String>,
+    pub protocol_version: u32,
+}
+
+impl ServerInfo {
+    pub fn supports(&self, capability: &str) -> bool {
+        self.capabilities.iter().any(|supported| supported == capability)
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct SessionTotals {
+    pub cost_usd: f64,
+    pub num_turns: u32,
+    pub requests: u32,
 }
 
 #[derive(Clone)]
@@ -91,10 +127,14 @@ impl StatusDisplay {
 #[derive(Clone)]
 pub struct AppState {
     pub connected: RwSignal<bool>,
+    pub server_info: RwSignal<Option<ServerInfo>>,
     pub status: RwSignal<StatusDisplay>,
     pub messages: RwSignal<Vec<ChatMessage>>,
     pub streaming_text: RwSignal<String>,
+    pub streaming_chunks: RwSignal<Vec<String>>,
     pub thinking_text: RwSignal<String>,
+    pub thinking_full: RwSignal<String>,
+    pub thinking_segments: RwSignal<Vec<String>>,
     pub current_session_id: RwSignal<Option<String>>,
     pub active_tools: RwSignal<Vec<ToolUseBlock>>,
     pub notifications: RwSignal<Vec<(String, String)>>,
@@ -102,6 +142,10 @@ pub struct AppState {
     pub active_tab: RwSignal<ActiveTab>,
     pub test_results: RwSignal<Vec<TestEntry>>,
     pub thinking_started_at: RwSignal<Option<f64>>,
+    pub config: RwSignal<Config>,
+    pub session_totals: RwSignal<SessionTotals>,
+    pub change_log: RwSignal<Vec<String>>,
+    pub history_depth: RwSignal<(usize, usize)>,
 }
 
 #[derive(Clone)]
@@ -115,10 +159,14 @@ impl AppState {
     pub fn new() -> Self {
         Self {
             connected: RwSignal::new(false),
+            server_info: RwSignal::new(None),
             status: RwSignal::new(StatusDisplay::Disconnected),
             messages: RwSignal::new(Vec::new()),
             streaming_text: RwSignal::new(String::new()),
+            streaming_chunks: RwSignal::new(Vec::new()),
             thinking_text: RwSignal::new(String::new()),
+            thinking_full: RwSignal::new(String::new()),
+            thinking_segments: RwSignal::new(Vec::new()),
             current_session_id: RwSignal::new(None),
             active_tools: RwSignal::new(Vec::new()),
             notifications: RwSignal::new(Vec::new()),
@@ -126,13 +174,84 @@ impl AppState {
             active_tab: RwSignal::new(ActiveTab::Chat),
             test_results: RwSignal::new(Vec::new()),
             thinking_started_at: RwSignal::new(None),
+            config: RwSignal::new(Config::default()),
+            session_totals: RwSignal::new(SessionTotals::default()),
+            change_log: RwSignal::new(Vec::new()),
+            history_depth: RwSignal::new((0, 0)),
+        }
+    }
+
+    pub fn push_system_message(&self, content: String) {
+        self.messages.update(|messages| {
+            messages.push(ChatMessage {
+                role: MessageRole::System,
+                content,
+                format: ContentFormat::Text,
+                thinking: Vec::new(),
+                thinking_duration_ms: 0,
+                tool_uses: Vec::new(),
+                image: None,
+                timestamp_ms: js_sys::Date::now(),
+            });
+        });
+    }
+
+    pub fn push_text_delta(&self, text: &str) {
+        let mut finished = None;
+        self.streaming_text.update(|tail| {
+            tail.push_str(text);
+            let split = match tail.rfind("\n\n") {
+                Some(index) => Some(index + 2),
+                None if tail.len() > MAX_STREAMING_TAIL_BYTES => tail.rfind('\n').map(|index| index + 1),
+                None => None,
+            };
+            if let Some(split) = split {
+                let rest = tail.split_off(split);
+                finished = Some(std::mem::replace(tail, rest));
+            }
+        });
+        if let Some(chunk) = finished {
+            self.streaming_chunks.update(|chunks| chunks.push(chunk));
+        }
+    }
+
+    pub fn clear_streaming_text(&self) {
+        self.streaming_text.set(String::new());
+        self.streaming_chunks.set(Vec::new());
+    }
+
+    pub fn push_thinking_delta(&self, text: &str) {
+        self.thinking_full.update_untracked(|full| full.push_str(text));
+        self.thinking_text.update(|live| {
+            live.push_str(text);
+            if live.len() > MAX_LIVE_THINKING_BYTES {
+                let mut tail_start = live.len() - MAX_LIVE_THINKING_BYTES;
+                while !live.is_char_boundary(tail_start) {
+                    tail_start += 1;
+                }
+                *live = format!("{THINKING_TRUNCATED_PREFIX}{}", &live[tail_start..]);
+            }
+        });
+    }
+
+    pub fn end_thinking_turn(&self) {
+        let thinking = self.thinking_full.get_untracked();
+        if !thinking.is_empty() {
+            self.thinking_segments.update(|segments| segments.push(thinking));
+            self.thinking_full.set(String::new());
+            self.thinking_text.set(String::new());
         }
     }
 
     pub fn finalize_streaming_message(&self) {
-        let text = self.streaming_text.get_untracked();
-        let thinking = self.thinking_text.get_untracked();
-        let tools = self.active_tools.get_untracked();
+        self.end_thinking_turn();
+        let mut text = self.streaming_chunks.get_untracked().concat();
+        text.push_str(&self.streaming_text.get_untracked());
+        let thinking = self.thinking_segments.get_untracked();
+        let mut tools = self.active_tools.get_untracked();
+        for tool in tools.iter_mut().filter(|tool| !tool.finished) {
+            tool.cancelled = true;
+        }
         let thinking_duration_ms = self.thinking_started_at.get_untracked()
             .map(|started| {
                 let now = js_sys::Date::now();
@@ -145,15 +264,18 @@ impl AppState {
                 messages.push(ChatMessage {
                     role: MessageRole::Assistant,
                     content: text,
+                    format: ContentFormat::Text,
                     thinking,
                     thinking_duration_ms,
                     tool_uses: tools,
+                    image: None,
+                    timestamp_ms: js_sys::Date::now(),
                 });
             });
         }
 
-        self.streaming_text.set(String::new());
-        self.thinking_text.set(String::new());
+        self.clear_streaming_text();
+        self.thinking_segments.set(Vec::new());
         self.thinking_started_at.set(None);
         self.active_tools.set(Vec::new());
     }
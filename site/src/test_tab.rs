@@ -30,6 +30,11 @@ const TESTS: &[TestDefinition] = &[
         label: "Display Content",
         description: "Backend sends markdown content via ContentDisplay. Verifies the content rendering pipeline.",
     },
+    TestDefinition {
+        name: "history_pipeline",
+        label: "History Pipeline",
+        description: "Records, undoes, branches, and redoes edits in a scratch history, checks the get_history JSON, then edits a probe cube and verifies undo and redo restore its transform and material.",
+    },
     TestDefinition {
         name: "status_cycle",
         label: "Status Cycle",
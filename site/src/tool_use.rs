@@ -8,6 +8,9 @@ pub fn ToolUseDisplay(tool: ToolUseBlock) -> impl IntoView {
     let tool_name = tool.tool_name.clone();
     let input_json = tool.input_json.clone();
     let finished = tool.finished;
+    let cancelled = tool.cancelled;
+    let result = tool.result.clone();
+    let is_error = tool.is_error;
 
     view! {
         <div class="my-2 border border-[#30363d] rounded-md overflow-hidden">
@@ -19,8 +22,12 @@ pub fn ToolUseDisplay(tool: ToolUseBlock) -> impl IntoView {
                     "▶"
                 </span>
                 <span class="text-purple-400 font-medium">{tool_name.clone()}</span>
-                {if finished {
+                {if is_error {
+                    view! { <span class="text-red-500 ml-auto">"✗"</span> }.into_any()
+                } else if finished {
                     view! { <span class="text-green-500 ml-auto">"✓"</span> }.into_any()
+                } else if cancelled {
+                    view! { <span class="text-[#8b949e] ml-auto" title="Cancelled before the tool finished">"⊘"</span> }.into_any()
                 } else {
                     view! { <span class="text-yellow-500 ml-auto animate-pulse">"⟳"</span> }.into_any()
                 }}
@@ -36,6 +43,20 @@ pub fn ToolUseDisplay(tool: ToolUseBlock) -> impl IntoView {
                     None
                 }
             }}
+            {move || {
+                let result = result.clone().filter(|_| expanded.get())?;
+                let result_class = if is_error {
+                    "px-3 py-2 text-xs text-red-400 bg-[#0d1117] overflow-x-auto whitespace-pre-wrap break-all max-h-64 overflow-y-auto"
+                } else {
+                    "px-3 py-2 text-xs text-[#c9d1d9] bg-[#0d1117] overflow-x-auto whitespace-pre-wrap break-all max-h-64 overflow-y-auto"
+                };
+                Some(view! {
+                    <div>
+                        <div class="px-3 pt-2 text-[10px] uppercase tracking-wide text-[#484f58] bg-[#0d1117] border-t border-[#30363d]">"Result"</div>
+                        <pre class=result_class>{result}</pre>
+                    </div>
+                })
+            }}
         </div>
     }
 }
@@ -1,16 +1,36 @@
 use leptos::prelude::*;
-use watchtower_protocol::FrontendCommand;
+use web_sys::wasm_bindgen::JsCast;
+use watchtower_protocol::{ASSEMBLE_DEMOS, FrontendCommand};
 
 use crate::state::{ActiveTab, AppState};
+use crate::transcript::download_transcript;
 
 #[component]
 pub fn Toolbar(state: AppState) -> impl IntoView {
     let status = state.status;
     let session_id = state.current_session_id;
     let active_tab = state.active_tab;
+    let session_totals = state.session_totals;
+    let messages = state.messages;
+    let server_info = state.server_info;
+    let history_depth = state.history_depth;
+    let assemble_supported = move || server_info.with(|info| info.as_ref().is_some_and(|info| info.supports("assemble")));
+
+    let (assemble_demo, set_assemble_demo) = signal(String::new());
+    let (assemble_seed, set_assemble_seed) = signal(String::new());
 
     let on_assemble = move |_| {
-        nightshade::webview::send(&FrontendCommand::Assemble);
+        let demo = assemble_demo.get_untracked();
+        nightshade::webview::send(&FrontendCommand::Assemble {
+            demo: if demo.is_empty() { None } else { Some(demo) },
+            seed: assemble_seed.get_untracked().trim().parse().ok(),
+        });
+    };
+
+    let on_export = move |_| {
+        messages.with_untracked(|messages| {
+            download_transcript(messages, &session_totals.get_untracked());
+        });
     };
 
     view! {
@@ -42,6 +62,30 @@ pub fn Toolbar(state: AppState) -> impl IntoView {
                     >
                         "Test"
                     </button>
+                    <button
+                        class=move || {
+                            if active_tab.get() == ActiveTab::Changes {
+                                "px-3 py-1 text-xs text-[#c9d1d9] border-b-2 border-[#58a6ff] cursor-pointer bg-transparent"
+                            } else {
+                                "px-3 py-1 text-xs text-[#484f58] hover:text-[#8b949e] border-b-2 border-transparent cursor-pointer bg-transparent"
+                            }
+                        }
+                        on:click=move |_| active_tab.set(ActiveTab::Changes)
+                    >
+                        "Changes"
+                    </button>
+                    <button
+                        class=move || {
+                            if active_tab.get() == ActiveTab::Settings {
+                                "px-3 py-1 text-xs text-[#c9d1d9] border-b-2 border-[#58a6ff] cursor-pointer bg-transparent"
+                            } else {
+                                "px-3 py-1 text-xs text-[#484f58] hover:text-[#8b949e] border-b-2 border-transparent cursor-pointer bg-transparent"
+                            }
+                        }
+                        on:click=move |_| active_tab.set(ActiveTab::Settings)
+                    >
+                        "Settings"
+                    </button>
                 </div>
                 <div class="flex items-center gap-2">
                     <div class={move || format!("w-2 h-2 rounded-full {}", status.get().dot_color_class())}></div>
@@ -57,12 +101,78 @@ pub fn Toolbar(state: AppState) -> impl IntoView {
                 </div>
             </div>
             <div class="flex items-center gap-3">
+                <div class="flex items-center gap-1">
+                    <button
+                        class="px-2 py-1 text-xs bg-[#21262d] text-[#c9d1d9] border border-[#30363d] rounded hover:bg-[#30363d] cursor-pointer disabled:opacity-50 disabled:cursor-not-allowed"
+                        title="Undo the last scene change"
+                        disabled=move || history_depth.get().0 == 0
+                        on:click=move |_| nightshade::webview::send(&FrontendCommand::Undo)
+                    >
+                        "↶ Undo"
+                        <sub class="ml-0.5 text-[#8b949e]">{move || history_depth.get().0}</sub>
+                    </button>
+                    <button
+                        class="px-2 py-1 text-xs bg-[#21262d] text-[#c9d1d9] border border-[#30363d] rounded hover:bg-[#30363d] cursor-pointer disabled:opacity-50 disabled:cursor-not-allowed"
+                        title="Redo the last undone scene change"
+                        disabled=move || history_depth.get().1 == 0
+                        on:click=move |_| nightshade::webview::send(&FrontendCommand::Redo)
+                    >
+                        "↷ Redo"
+                        <sub class="ml-0.5 text-[#8b949e]">{move || history_depth.get().1}</sub>
+                    </button>
+                </div>
+                <div class="flex items-center gap-1" class:hidden=move || !assemble_supported()>
+                    <select
+                        class="bg-[#0d1117] text-[#c9d1d9] border border-[#30363d] rounded px-2 py-1 text-xs cursor-pointer focus:outline-none focus:border-[#58a6ff]"
+                        on:change=move |event| {
+                            let select: web_sys::HtmlSelectElement = event.target().unwrap().unchecked_into();
+                            set_assemble_demo.set(select.value());
+                        }
+                    >
+                        <option value="">"Cycle"</option>
+                        {ASSEMBLE_DEMOS.iter().map(|demo| view! {
+                            <option value=*demo>{demo.replace('_', " ")}</option>
+                        }).collect_view()}
+                    </select>
+                    <input
+                        type="number"
+                        min="0"
+                        class="w-20 bg-[#0d1117] text-[#c9d1d9] border border-[#30363d] rounded px-2 py-1 text-xs focus:outline-none focus:border-[#58a6ff] placeholder-[#484f58]"
+                        placeholder="seed"
+                        prop:value=move || assemble_seed.get()
+                        on:input=move |event| {
+                            let input: web_sys::HtmlInputElement = event.target().unwrap().unchecked_into();
+                            set_assemble_seed.set(input.value());
+                        }
+                    />
+                    <button
+                        class="px-3 py-1 text-xs bg-[#238636] text-white rounded hover:bg-[#2ea043] cursor-pointer"
+                        on:click=on_assemble
+                    >
+                        "Assemble"
+                    </button>
+                </div>
+                {move || {
+                    let totals = session_totals.get();
+                    (totals.requests > 0).then(|| view! {
+                        <div class="text-xs text-[#8b949e]" title="Cumulative cost and turns for this session">
+                            {format!("${:.4} · {} turns", totals.cost_usd, totals.num_turns)}
+                        </div>
+                    })
+                }}
                 <button
-                    class="px-3 py-1 text-xs bg-[#238636] text-white rounded hover:bg-[#2ea043] cursor-pointer"
-                    on:click=on_assemble
+                    class="px-3 py-1 text-xs bg-[#21262d] text-[#c9d1d9] border border-[#30363d] rounded hover:bg-[#30363d] cursor-pointer disabled:opacity-50 disabled:cursor-not-allowed"
+                    title="Download the conversation as a markdown file"
+                    disabled=move || messages.with(Vec::is_empty)
+                    on:click=on_export
                 >
-                    "Assemble"
+                    "Export"
                 </button>
+                {move || server_info.get().map(|info| view! {
+                    <div class="text-xs text-[#484f58]" title=format!("Protocol v{} · Capabilities: {}", info.protocol_version, info.capabilities.join(", "))>
+                        {format!("v{} · MCP :{}", info.version, info.mcp_port)}
+                    </div>
+                })}
                 <div class="text-xs text-[#484f58]">
                     {move || session_id.get().map(|id| {
                         if id.len() > 12 {
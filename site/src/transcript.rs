@@ -0,0 +1,82 @@
+use watchtower_protocol::ContentFormat;
+use web_sys::wasm_bindgen::{JsCast, JsValue};
+
+use crate::state::{ChatMessage, MessageRole, SessionTotals};
+
+fn format_timestamp(timestamp_ms: f64) -> String {
+    String::from(js_sys::Date::new(&JsValue::from_f64(timestamp_ms)).to_iso_string())
+}
+
+pub fn transcript_markdown(messages: &[ChatMessage], totals: &SessionTotals) -> String {
+    let mut markdown = String::from("# Watchtower Transcript\n\n");
+    markdown.push_str(&format!("Exported: {}\n\n", format_timestamp(js_sys::Date::now())));
+    markdown.push_str(&format!(
+        "Session: {} requests, {} turns, ${:.4}\n",
+        totals.requests, totals.num_turns, totals.cost_usd
+    ));
+
+    for message in messages {
+        let role = match &message.role {
+            MessageRole::User => "User".to_string(),
+            MessageRole::Assistant => "Assistant".to_string(),
+            MessageRole::System => "System".to_string(),
+            MessageRole::ToolError { tool_name } => format!("Tool Error ({tool_name})"),
+        };
+        markdown.push_str(&format!("\n## {role} · {}\n\n", format_timestamp(message.timestamp_ms)));
+
+        if !message.thinking.is_empty() {
+            markdown.push_str(&format!("<details><summary>Thinking ({} ms)</summary>\n\n", message.thinking_duration_ms));
+            markdown.push_str(&message.thinking.join("\n\n"));
+            markdown.push_str("\n\n</details>\n\n");
+        }
+
+        if !message.content.is_empty() {
+            if matches!(message.format, ContentFormat::Text | ContentFormat::Markdown) {
+                markdown.push_str(&message.content);
+                markdown.push_str("\n\n");
+            } else {
+                let language = if matches!(message.format, ContentFormat::Diff) { "diff" } else { "" };
+                markdown.push_str(&format!("```{language}\n{}\n```\n\n", message.content));
+            }
+        }
+
+        if message.image.is_some() {
+            markdown.push_str("_(image attached)_\n\n");
+        }
+
+        for tool in &message.tool_uses {
+            let outcome = if tool.is_error { " (error)" } else { "" };
+            markdown.push_str(&format!("**Tool: {}**{outcome}\n\n```json\n{}\n```\n\n", tool.tool_name, tool.input_json));
+            if let Some(result) = &tool.result {
+                markdown.push_str(&format!("```\n{result}\n```\n\n"));
+            }
+        }
+    }
+
+    markdown
+}
+
+pub fn download_transcript(messages: &[ChatMessage], totals: &SessionTotals) {
+    let markdown = transcript_markdown(messages, totals);
+    let Some(document) = web_sys::window().and_then(|window| window.document()) else {
+        return;
+    };
+
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type("text/markdown");
+    let parts = js_sys::Array::of1(&JsValue::from_str(&markdown));
+    let Ok(blob) = web_sys::Blob::new_with_str_sequence_and_options(&parts, &options) else {
+        return;
+    };
+    let Ok(url) = web_sys::Url::create_object_url_with_blob(&blob) else {
+        return;
+    };
+
+    if let Ok(element) = document.create_element("a") {
+        let anchor: web_sys::HtmlAnchorElement = element.unchecked_into();
+        anchor.set_href(&url);
+        anchor.set_download(&format!("watchtower-transcript-{}.md", js_sys::Date::now() as u64));
+        anchor.click();
+    }
+    let _ = web_sys::Url::revoke_object_url(&url);
+}
@@ -1,7 +1,10 @@
-use std::io::BufRead;
+use std::io::{BufRead, Write};
 use std::os::windows::process::CommandExt;
-use std::process::{Child, Command, Stdio};
-use std::sync::mpsc::{Receiver, Sender};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::{Arc, Mutex};
+
+use base64::Engine;
+use std::sync::mpsc::{Receiver, SyncSender};
 
 const CREATE_NO_WINDOW: u32 = 0x08000000;
 
@@ -10,10 +13,21 @@ pub enum CliCommand {
         prompt: String,
         session_id: Option<String>,
         model: Option<String>,
+        image: Option<PromptImage>,
+    },
+    PermissionDecision {
+        request_id: String,
+        allow: bool,
+        input: serde_json::Value,
     },
     Cancel,
 }
 
+pub struct PromptImage {
+    pub media_type: String,
+    pub data: String,
+}
+
 pub enum CliEvent {
     SessionStarted { session_id: String },
     TextDelta { text: String },
@@ -21,6 +35,8 @@ pub enum CliEvent {
     ToolUseStarted { tool_name: String, tool_id: String },
     ToolUseInputDelta { tool_id: String, partial_json: String },
     ToolUseFinished { tool_id: String },
+    ToolResult { tool_id: String, content: String, is_error: bool },
+    PermissionRequest { request_id: String, tool: String, input: serde_json::Value },
     TurnComplete { session_id: String },
     Complete { session_id: String, total_cost_usd: Option<f64>, num_turns: u32 },
     Error { message: String },
@@ -28,15 +44,16 @@ pub enum CliEvent {
 
 pub fn spawn_cli_worker(
     command_receiver: Receiver<CliCommand>,
-    event_sender: Sender<CliEvent>,
+    event_sender: SyncSender<CliEvent>,
 ) {
     std::thread::spawn(move || {
         let mut current_child: Option<Child> = None;
         let mut current_session_id = String::new();
+        let current_stdin: Arc<Mutex<Option<ChildStdin>>> = Arc::new(Mutex::new(None));
 
         loop {
             match command_receiver.recv() {
-                Ok(CliCommand::StartQuery { prompt, session_id, model }) => {
+                Ok(CliCommand::StartQuery { prompt, session_id, model, image }) => {
                     if let Some(mut child) = current_child.take() {
                         let _ = child.kill();
                         let _ = child.wait();
@@ -44,7 +61,10 @@ pub fn spawn_cli_worker(
 
                     let mut args = vec![
                         "-p".to_string(),
-                        prompt,
+                        "--input-format".to_string(),
+                        "stream-json".to_string(),
+                        "--permission-prompt-tool".to_string(),
+                        "stdio".to_string(),
                         "--output-format".to_string(),
                         "stream-json".to_string(),
                         "--verbose".to_string(),
@@ -63,6 +83,7 @@ pub fn spawn_cli_worker(
 
                     let mut cmd = Command::new("claude");
                     cmd.args(&args)
+                        .stdin(Stdio::piped())
                         .stdout(Stdio::piped())
                         .stderr(Stdio::piped())
                         .creation_flags(CREATE_NO_WINDOW)
@@ -72,14 +93,30 @@ pub fn spawn_cli_worker(
                         Ok(mut child) => {
                             let stdout = child.stdout.take().expect("stdout was piped");
                             let stderr = child.stderr.take().expect("stderr was piped");
+                            let mut stdin = child.stdin.take().expect("stdin was piped");
                             current_child = Some(child);
 
+                            let user_message = serde_json::json!({
+                                "type": "user",
+                                "message": {
+                                    "role": "user",
+                                    "content": prompt_content(&prompt, image.as_ref()),
+                                },
+                            });
+                            if let Err(error) = writeln!(stdin, "{user_message}") {
+                                let _ = event_sender.send(CliEvent::Error {
+                                    message: format!("Failed to send prompt to claude CLI: {error}"),
+                                });
+                            }
+                            *current_stdin.lock().unwrap() = Some(stdin);
+
                             std::thread::spawn(move || {
                                 let reader = std::io::BufReader::new(stderr);
                                 for _ in reader.lines() {}
                             });
 
                             let event_sender_clone = event_sender.clone();
+                            let stdin_for_reader = current_stdin.clone();
 
                             std::thread::spawn(move || {
                                 let reader = std::io::BufReader::new(stdout);
@@ -102,6 +139,9 @@ pub fn spawn_cli_worker(
 
                                     let events = parse_stream_json_line(&json_value, &mut session_id);
                                     for event in events {
+                                        if matches!(event, CliEvent::Complete { .. }) {
+                                            stdin_for_reader.lock().unwrap().take();
+                                        }
                                         if event_sender_clone.send(event).is_err() {
                                             return;
                                         }
@@ -118,7 +158,33 @@ pub fn spawn_cli_worker(
                         }
                     }
                 }
+                Ok(CliCommand::PermissionDecision { request_id, allow, input }) => {
+                    let response = if allow {
+                        serde_json::json!({ "behavior": "allow", "updatedInput": input })
+                    } else {
+                        serde_json::json!({ "behavior": "deny", "message": "Denied by user in Watchtower" })
+                    };
+                    let control_response = serde_json::json!({
+                        "type": "control_response",
+                        "response": {
+                            "subtype": "success",
+                            "request_id": request_id,
+                            "response": response,
+                        },
+                    });
+                    let mut stdin_guard = current_stdin.lock().unwrap();
+                    let written = stdin_guard
+                        .as_mut()
+                        .map(|stdin| writeln!(stdin, "{control_response}").is_ok())
+                        .unwrap_or(false);
+                    if !written {
+                        let _ = event_sender.send(CliEvent::Error {
+                            message: "Failed to send permission decision: claude CLI is no longer running".to_string(),
+                        });
+                    }
+                }
                 Ok(CliCommand::Cancel) => {
+                    current_stdin.lock().unwrap().take();
                     if let Some(mut child) = current_child.take() {
                         let _ = child.kill();
                         let _ = child.wait();
@@ -133,6 +199,42 @@ pub fn spawn_cli_worker(
     });
 }
 
+pub fn parse_prompt_image(data_url: &str) -> Result<PromptImage, String> {
+    let (header, payload) = data_url
+        .strip_prefix("data:")
+        .and_then(|rest| rest.split_once(";base64,"))
+        .ok_or_else(|| "Pasted image is not a base64 data URL".to_string())?;
+
+    if !matches!(header, "image/png" | "image/jpeg" | "image/gif" | "image/webp") {
+        return Err(format!("Unsupported pasted image type: {header}"));
+    }
+
+    base64::engine::general_purpose::STANDARD
+        .decode(payload)
+        .map_err(|error| format!("Failed to decode pasted image: {error}"))?;
+
+    Ok(PromptImage {
+        media_type: header.to_string(),
+        data: payload.to_string(),
+    })
+}
+
+fn prompt_content(prompt: &str, image: Option<&PromptImage>) -> serde_json::Value {
+    let mut content = Vec::new();
+    if let Some(image) = image {
+        content.push(serde_json::json!({
+            "type": "image",
+            "source": {
+                "type": "base64",
+                "media_type": image.media_type,
+                "data": image.data,
+            },
+        }));
+    }
+    content.push(serde_json::json!({ "type": "text", "text": prompt }));
+    serde_json::Value::Array(content)
+}
+
 fn parse_stream_json_line(value: &serde_json::Value, session_id: &mut String) -> Vec<CliEvent> {
     let mut events = Vec::new();
 
@@ -219,6 +321,51 @@ fn parse_stream_json_line(value: &serde_json::Value, session_id: &mut String) ->
             }
         }
 
+        "user" => {
+            let blocks = value.get("message")
+                .and_then(|message| message.get("content"))
+                .and_then(|content| content.as_array());
+            for block in blocks.into_iter().flatten() {
+                if block.get("type").and_then(|v| v.as_str()) != Some("tool_result") {
+                    continue;
+                }
+                let tool_id = block.get("tool_use_id")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string();
+                let is_error = block.get("is_error").and_then(|v| v.as_bool()).unwrap_or(false);
+                let content = match block.get("content") {
+                    Some(serde_json::Value::String(text)) => text.clone(),
+                    Some(serde_json::Value::Array(parts)) => parts
+                        .iter()
+                        .filter_map(|part| part.get("text").and_then(|v| v.as_str()))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                    _ => String::new(),
+                };
+                events.push(CliEvent::ToolResult { tool_id, content, is_error });
+            }
+        }
+
+        "control_request" => {
+            let request = value.get("request");
+            let subtype = request.and_then(|r| r.get("subtype")).and_then(|v| v.as_str()).unwrap_or("");
+            if subtype == "can_use_tool" {
+                let request_id = value.get("request_id")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string();
+                let tool = request.and_then(|r| r.get("tool_name"))
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("unknown")
+                    .to_string();
+                let input = request.and_then(|r| r.get("input"))
+                    .cloned()
+                    .unwrap_or(serde_json::Value::Null);
+                events.push(CliEvent::PermissionRequest { request_id, tool, input });
+            }
+        }
+
         "result" => {
             let total_cost = value.get("total_cost_usd").and_then(|v| v.as_f64());
             let num_turns = value.get("num_turns").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
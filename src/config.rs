@@ -0,0 +1,51 @@
+use std::path::PathBuf;
+
+use watchtower_protocol::Config;
+
+const CONFIG_FILE_NAME: &str = "watchtower_config.json";
+
+pub fn config_path() -> Option<PathBuf> {
+    let base = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("watchtower").join(CONFIG_FILE_NAME))
+}
+
+pub fn load_config() -> Config {
+    config_path()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_config(config: &Config) -> Result<PathBuf, String> {
+    let path = config_path().ok_or_else(|| "No user config directory available".to_string())?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|error| format!("Failed to create config directory: {error}"))?;
+    }
+    let json = serde_json::to_string_pretty(config).map_err(|error| format!("Failed to serialize config: {error}"))?;
+    std::fs::write(&path, json).map_err(|error| format!("Failed to write config: {error}"))?;
+    Ok(path)
+}
+
+pub fn with_env_overrides(config: &Config) -> Config {
+    let mut effective = config.clone();
+    if let Ok(model) = std::env::var("WATCHTOWER_MODEL") {
+        effective.default_model = Some(model);
+    }
+    if let Some(timeout) = std::env::var("WATCHTOWER_CLI_TEST_TIMEOUT_SECS").ok().and_then(|value| value.parse().ok()) {
+        effective.cli_test_timeout_secs = timeout;
+    }
+    if let Ok(shadows) = std::env::var("WATCHTOWER_SHADOWS") {
+        effective.shadows_enabled = shadows != "0" && !shadows.eq_ignore_ascii_case("false");
+    }
+    if let Ok(keyboard_camera) = std::env::var("WATCHTOWER_KEYBOARD_CAMERA") {
+        effective.keyboard_camera = keyboard_camera != "0" && !keyboard_camera.eq_ignore_ascii_case("false");
+    }
+    if let Ok(preserve_camera) = std::env::var("WATCHTOWER_PRESERVE_CAMERA") {
+        effective.preserve_camera_on_assemble = preserve_camera != "0" && !preserve_camera.eq_ignore_ascii_case("false");
+    }
+    effective
+}
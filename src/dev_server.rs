@@ -0,0 +1,65 @@
+use std::path::{Component, Path, PathBuf};
+
+use axum::http::{StatusCode, Uri, header};
+use axum::response::{IntoResponse, Response};
+
+pub fn site_dist_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("site").join("dist")
+}
+
+pub fn serve_site_from_disk(root: PathBuf) -> u16 {
+    let std_listener = std::net::TcpListener::bind("127.0.0.1:0").expect("failed to bind dev site server");
+    let port = std_listener.local_addr().map(|address| address.port()).unwrap_or(0);
+    std_listener.set_nonblocking(true).expect("failed to configure dev site server");
+
+    std::thread::spawn(move || {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async move {
+            let tcp_listener = tokio::net::TcpListener::from_std(std_listener).unwrap();
+            let router = axum::Router::new().fallback(move |uri: Uri| {
+                let root = root.clone();
+                async move { serve_file(&root, uri.path()).await }
+            });
+
+            axum::serve(tcp_listener, router).await.ok();
+        });
+    });
+
+    port
+}
+
+async fn serve_file(root: &Path, request_path: &str) -> Response {
+    let relative = request_path.trim_start_matches('/');
+    let relative = if relative.is_empty() { "index.html" } else { relative };
+    let relative = Path::new(relative);
+    if relative.components().any(|component| !matches!(component, Component::Normal(_))) {
+        return StatusCode::BAD_REQUEST.into_response();
+    }
+
+    let path = root.join(relative);
+    match tokio::fs::read(&path).await {
+        Ok(bytes) => (
+            [
+                (header::CONTENT_TYPE, content_type(&path)),
+                (header::CACHE_CONTROL, "no-store"),
+            ],
+            bytes,
+        )
+            .into_response(),
+        Err(_) => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+fn content_type(path: &Path) -> &'static str {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("html") => "text/html; charset=utf-8",
+        Some("js") => "application/javascript",
+        Some("wasm") => "application/wasm",
+        Some("css") => "text/css",
+        Some("json") => "application/json",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("ico") => "image/x-icon",
+        _ => "application/octet-stream",
+    }
+}
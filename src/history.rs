@@ -0,0 +1,364 @@
+use std::collections::{HashMap, VecDeque};
+use nightshade::prelude::*;
+
+use crate::mcp_server::MaterialOverrides;
+use crate::scene::{
+    EmissivePulse, Keyframe, Orbit, SceneState, Trail, Velocity, spawn_gltf_at, spawn_shape_at, write_entity_material,
+};
+
+pub const MAX_HISTORY_ENTRIES: usize = 100;
+
+#[derive(Clone, PartialEq)]
+pub struct EntitySnapshot {
+    pub shape: Option<String>,
+    pub translation: nalgebra_glm::Vec3,
+    pub rotation: nalgebra_glm::Quat,
+    pub scale: nalgebra_glm::Vec3,
+    pub material: Option<MaterialOverrides>,
+    pub gltf_path: Option<String>,
+    pub orbit: Option<Orbit>,
+    pub velocity: Option<Velocity>,
+    pub billboard: bool,
+    pub emissive_pulse: Option<EmissivePulse>,
+    pub keyframes: Vec<Keyframe>,
+    pub trail: Option<(usize, [f32; 3])>,
+}
+
+pub type SceneSnapshot = Vec<(String, Option<EntitySnapshot>)>;
+
+pub struct HistoryEntry {
+    pub label: String,
+    pub before: SceneSnapshot,
+    pub after: SceneSnapshot,
+}
+
+#[derive(Default)]
+pub struct History {
+    undo_stack: VecDeque<HistoryEntry>,
+    redo_stack: Vec<HistoryEntry>,
+}
+
+impl History {
+    pub fn record(&mut self, entry: HistoryEntry) {
+        if self.undo_stack.len() == MAX_HISTORY_ENTRIES {
+            self.undo_stack.pop_front();
+        }
+        self.undo_stack.push_back(entry);
+        self.redo_stack.clear();
+    }
+
+    pub fn undo(&mut self) -> Option<&HistoryEntry> {
+        let entry = self.undo_stack.pop_back()?;
+        self.redo_stack.push(entry);
+        self.redo_stack.last()
+    }
+
+    pub fn redo(&mut self) -> Option<&HistoryEntry> {
+        let entry = self.redo_stack.pop()?;
+        self.undo_stack.push_back(entry);
+        self.undo_stack.back()
+    }
+
+    pub fn undo_depth(&self) -> usize {
+        self.undo_stack.len()
+    }
+
+    pub fn redo_depth(&self) -> usize {
+        self.redo_stack.len()
+    }
+
+    pub fn undo_labels(&self) -> Vec<&str> {
+        self.undo_stack.iter().rev().map(|entry| entry.label.as_str()).collect()
+    }
+
+    pub fn redo_labels(&self) -> Vec<&str> {
+        self.redo_stack.iter().rev().map(|entry| entry.label.as_str()).collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "undo_depth": self.undo_depth(),
+            "redo_depth": self.redo_depth(),
+            "undo": self.undo_labels(),
+            "redo": self.redo_labels(),
+        })
+    }
+}
+
+pub fn verify_history_pipeline() -> Result<(), String> {
+    let mut history = History::default();
+    let record = |history: &mut History, label: &str| history.record(HistoryEntry {
+        label: label.to_string(),
+        before: Vec::new(),
+        after: Vec::new(),
+    });
+
+    let expect = |step: &str, history: &History, undo: &[&str], redo: &[&str]| {
+        let expected = serde_json::json!({
+            "undo_depth": undo.len(),
+            "redo_depth": redo.len(),
+            "undo": undo,
+            "redo": redo,
+        });
+        let actual = history.to_json();
+        if actual == expected {
+            Ok(())
+        } else {
+            Err(format!("after {step}: expected {expected}, got {actual}"))
+        }
+    };
+
+    for label in ["spawn a", "move a", "paint a"] {
+        record(&mut history, label);
+    }
+    expect("three edits", &history, &["paint a", "move a", "spawn a"], &[])?;
+
+    history.undo();
+    history.undo();
+    expect("two undos", &history, &["spawn a"], &["move a", "paint a"])?;
+
+    record(&mut history, "scale a");
+    expect("branching edit", &history, &["scale a", "spawn a"], &[])?;
+    if history.redo().is_some() {
+        return Err("redo succeeded after a branching edit".to_string());
+    }
+
+    let undone = history.undo().map(|entry| entry.label.clone());
+    if undone.as_deref() != Some("scale a") {
+        return Err(format!("undo returned {undone:?}, expected \"scale a\""));
+    }
+    let redone = history.redo().map(|entry| entry.label.clone());
+    if redone.as_deref() != Some("scale a") {
+        return Err(format!("redo returned {redone:?}, expected \"scale a\""));
+    }
+    expect("undo and redo", &history, &["scale a", "spawn a"], &[])?;
+
+    for index in 0..MAX_HISTORY_ENTRIES + 5 {
+        record(&mut history, &format!("edit {index}"));
+    }
+    if history.undo_depth() != MAX_HISTORY_ENTRIES {
+        return Err(format!("undo depth {} exceeds the cap of {MAX_HISTORY_ENTRIES}", history.undo_depth()));
+    }
+    Ok(())
+}
+
+pub fn verify_history_restore(world: &mut World) -> Result<(), String> {
+    let name = "history_probe".to_string();
+    let names = [name.clone()];
+    let mut scene = SceneState::default();
+    let entity = spawn_shape_at(world, "cube", nalgebra_glm::vec3(0.0, 0.0, 0.0))
+        .ok_or("failed to spawn the probe cube")?;
+    scene.entities.insert(name.clone(), entity);
+    scene.shapes.insert(name.clone(), "cube".to_string());
+
+    let result = (|| {
+        let before = snapshot_entities(&scene, world, &names);
+        if let Some(transform) = world.get_local_transform_mut(entity) {
+            transform.translation = nalgebra_glm::vec3(1.0, 2.0, 3.0);
+            transform.scale = nalgebra_glm::vec3(2.0, 2.0, 2.0);
+        }
+        world.set_local_transform_dirty(entity, LocalTransformDirty);
+        let material = MaterialOverrides {
+            color: Some([1.0, 0.0, 0.0, 1.0]),
+            roughness: Some(0.2),
+            ..Default::default()
+        };
+        write_entity_material(world, entity, &name, &material);
+        scene.materials.insert(name.clone(), material);
+        let after = snapshot_entities(&scene, world, &names);
+        if before == after {
+            return Err("editing the probe did not change its snapshot".to_string());
+        }
+        scene.history.record(HistoryEntry { label: "edit probe".to_string(), before, after });
+
+        let expect = |step: &str, scene: &SceneState, world: &World, expected: &SceneSnapshot| {
+            let Some((_, Some(state))) = expected.first() else {
+                return Err(format!("after {step}: expected snapshot is empty"));
+            };
+            let transform = scene.entities.get(&name)
+                .and_then(|&entity| world.get_local_transform(entity))
+                .ok_or_else(|| format!("after {step}: probe entity is missing"))?;
+            if transform.translation != state.translation || transform.rotation != state.rotation || transform.scale != state.scale {
+                return Err(format!("after {step}: transform was not restored"));
+            }
+            if scene.materials.get(&name) != state.material.as_ref() {
+                return Err(format!("after {step}: material was not restored"));
+            }
+            Ok(())
+        };
+
+        let before = scene.history.undo().map(|entry| entry.before.clone()).ok_or("undo returned nothing")?;
+        restore_snapshot(&mut scene, world, &before);
+        expect("undo", &scene, world, &before)?;
+
+        let after = scene.history.redo().map(|entry| entry.after.clone()).ok_or("redo returned nothing")?;
+        restore_snapshot(&mut scene, world, &after);
+        expect("redo", &scene, world, &after)
+    })();
+
+    if let Some(entity) = scene.entities.remove(&name) {
+        despawn_recursive_immediate(world, entity);
+    }
+    result
+}
+
+pub fn snapshot_entities(scene: &SceneState, world: &World, names: &[String]) -> SceneSnapshot {
+    names.iter().map(|name| {
+        let snapshot = scene.entities.get(name)
+            .and_then(|&entity| world.get_local_transform(entity))
+            .map(|transform| EntitySnapshot {
+                shape: scene.shapes.get(name).cloned(),
+                translation: transform.translation,
+                rotation: transform.rotation,
+                scale: scene.revealing_scale(name).unwrap_or(transform.scale),
+                material: scene.materials.get(name).cloned(),
+                gltf_path: scene.gltf_sources.get(name).cloned(),
+                orbit: scene.orbits.get(name).copied(),
+                velocity: scene.velocities.get(name).copied(),
+                billboard: scene.billboards.contains(name),
+                emissive_pulse: scene.emissive_pulses.get(name).copied(),
+                keyframes: scene.keyframes.get(name).cloned().unwrap_or_default(),
+                trail: scene.trails.get(name).map(|trail| (trail.length, trail.color)),
+            });
+        (name.clone(), snapshot)
+    }).collect()
+}
+
+#[derive(Default)]
+pub struct RestoreReport {
+    pub unrecreatable: Vec<String>,
+    pub locked: Vec<String>,
+}
+
+pub fn restore_snapshot(scene: &mut SceneState, world: &mut World, snapshot: &SceneSnapshot) -> RestoreReport {
+    let mut report = RestoreReport::default();
+    for (name, state) in snapshot {
+        if scene.locked.contains(name) {
+            report.locked.push(name.clone());
+            continue;
+        }
+        let Some(state) = state else {
+            if let Some(entity) = scene.entities.remove(name) {
+                despawn_recursive_immediate(world, entity);
+                scene.forget_entity(name);
+            }
+            continue;
+        };
+
+        let entity = match scene.entities.get(name) {
+            Some(&entity) => entity,
+            None => {
+                let spawned = match (&state.shape, &state.gltf_path) {
+                    (Some(shape), _) => spawn_shape_at(world, shape, state.translation),
+                    (None, Some(path)) => spawn_gltf_at(world, path, state.translation).ok().map(|imported| imported.entity),
+                    (None, None) => None,
+                };
+                let Some(entity) = spawned else {
+                    report.unrecreatable.push(name.clone());
+                    continue;
+                };
+                scene.entities.insert(name.clone(), entity);
+                if let Some(shape) = &state.shape {
+                    scene.shapes.insert(name.clone(), shape.clone());
+                }
+                if let Some(path) = &state.gltf_path {
+                    scene.gltf_sources.insert(name.clone(), path.clone());
+                }
+                entity
+            }
+        };
+
+        scene.cancel_spawn_reveal(name);
+        if let Some(transform) = world.get_local_transform_mut(entity) {
+            transform.translation = state.translation;
+            transform.rotation = state.rotation;
+            transform.scale = state.scale;
+        }
+        world.set_local_transform_dirty(entity, LocalTransformDirty);
+
+        match &state.material {
+            Some(material) => {
+                write_entity_material(world, entity, name, material);
+                scene.materials.insert(name.clone(), material.clone());
+            }
+            None => {
+                if scene.materials.remove(name).is_some() {
+                    write_entity_material(world, entity, name, &MaterialOverrides::default());
+                }
+            }
+        }
+
+        restore_entry(&mut scene.orbits, name, state.orbit);
+        restore_entry(&mut scene.velocities, name, state.velocity);
+        restore_entry(&mut scene.emissive_pulses, name, state.emissive_pulse);
+        restore_entry(&mut scene.keyframes, name, Some(state.keyframes.clone()).filter(|keyframes| !keyframes.is_empty()));
+        if state.billboard {
+            scene.billboards.insert(name.clone());
+        } else {
+            scene.billboards.remove(name);
+        }
+        if scene.trails.get(name).map(|trail| (trail.length, trail.color)) != state.trail {
+            if let Some(trail) = scene.trails.remove(name) {
+                trail.despawn(world);
+            }
+            if let Some((length, color)) = state.trail {
+                scene.trails.insert(name.clone(), Trail::new(length, color));
+            }
+        }
+    }
+    report
+}
+
+fn restore_entry<T>(map: &mut HashMap<String, T>, name: &str, value: Option<T>) {
+    match value {
+        Some(value) => {
+            map.insert(name.to_string(), value);
+        }
+        None => {
+            map.remove(name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(history: &mut History, label: &str) {
+        history.record(HistoryEntry {
+            label: label.to_string(),
+            before: Vec::new(),
+            after: Vec::new(),
+        });
+    }
+
+    #[test]
+    fn depths_track_undo_and_redo() {
+        let mut history = History::default();
+        assert_eq!((history.undo_depth(), history.redo_depth()), (0, 0));
+
+        for label in ["spawn a", "move a", "paint a"] {
+            record(&mut history, label);
+        }
+        assert_eq!((history.undo_depth(), history.redo_depth()), (3, 0));
+
+        history.undo();
+        history.undo();
+        assert_eq!((history.undo_depth(), history.redo_depth()), (1, 2));
+
+        history.redo();
+        assert_eq!((history.undo_depth(), history.redo_depth()), (2, 1));
+
+        record(&mut history, "scale a");
+        assert_eq!((history.undo_depth(), history.redo_depth()), (3, 0));
+
+        let json = history.to_json();
+        assert_eq!(json["undo_depth"], 3);
+        assert_eq!(json["redo_depth"], 0);
+    }
+}
@@ -1,70 +1,183 @@
 #![windows_subsystem = "windows"]
 
 mod cli;
+mod config;
+mod dev_server;
+mod history;
+mod mcp_log;
 mod mcp_server;
+mod mcp_validation;
 mod scene;
+mod stats;
 
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, mpsc};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use include_dir::{Dir, include_dir};
 use nightshade::ecs::camera::spawn_pan_orbit_camera;
 use nightshade::prelude::*;
 use nightshade::webview::{WebviewContext, serve_embedded_dir};
-use watchtower_protocol::{AgentStatus, BackendEvent, ContentFormat, FrontendCommand};
+use watchtower_protocol::{
+    ASSEMBLE_DEMOS, AgentStatus, BackendEvent, Config, ContentFormat, FrontendCommand, PROTOCOL_VERSION,
+};
 
-use crate::cli::{CliCommand, CliEvent, spawn_cli_worker};
+use crate::cli::{CliCommand, CliEvent, parse_prompt_image, spawn_cli_worker};
+use crate::config::{load_config, save_config, with_env_overrides};
+use crate::dev_server::{serve_site_from_disk, site_dist_dir};
+use crate::history::{HistoryEntry, restore_snapshot, snapshot_entities, verify_history_pipeline, verify_history_restore};
+use crate::mcp_log::{McpLogger, McpReplay};
 use crate::mcp_server::{
-    McpCommand, McpResponse, WatchtowerCommandQueue, WatchtowerResponseQueue,
-    create_watchtower_mcp_queues, start_watchtower_mcp_server,
+    MAX_LIST_ENTITIES, MCP_PORT, MaterialOverrides, McpCommand, McpResponse, QueuedCommand, WatchtowerCommandQueue,
+    WatchtowerResponseQueue, create_watchtower_mcp_queues, start_watchtower_mcp_server,
+};
+use crate::scene::{
+    CameraFollow, EmissivePulse, Keyframe, LayoutJitter, MATERIAL_PRESETS, Orbit, PREFABS, SHAPES, SceneState, Trail,
+    Velocity, compose_transform_matrix, decompose_transform_matrix, describe_offset, euler_degrees_to_quat,
+    find_material_preset, find_prefab, load_texture_file, sample_keyframes, shape_half_extents, spawn_gltf_at,
+    spawn_shape_at, write_entity_material,
 };
-use crate::scene::SceneState;
+use crate::stats::{FrameStats, MAX_FRAME_STAT_SAMPLES, SceneMetrics, performance_recommendations};
 
 static DIST: Dir = include_dir!("$CARGO_MANIFEST_DIR/site/dist");
 
+const IDLE_REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+const ACTIVITY_REDRAW_GRACE: Duration = Duration::from_secs(1);
+const HIGHLIGHT_EMISSIVE: [f32; 3] = [4.0, 3.2, 0.8];
+const HIGHLIGHT_PULSE_SPEED: f32 = 6.0;
+const MAX_HIGHLIGHT_SECONDS: f32 = 30.0;
+const KEYBOARD_PAN_SPEED: f32 = 0.75;
+const KEYBOARD_ZOOM_SPEED: f32 = 1.5;
+const TEST_RESULT_CHANNEL_CAPACITY: usize = 64;
+const CLI_EVENT_CHANNEL_CAPACITY: usize = 1024;
+const MAX_REPLAYED_CHANGES: usize = 500;
+const CAPABILITIES: &[&str] = &["scene_3d", "assemble", "permission_prompts", "change_log", "config"];
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let (cli_cmd_tx, cli_cmd_rx) = mpsc::channel::<CliCommand>();
-    let (cli_event_tx, cli_event_rx) = mpsc::channel::<CliEvent>();
+    let (cli_event_tx, cli_event_rx) = mpsc::sync_channel::<CliEvent>(CLI_EVENT_CHANNEL_CAPACITY);
 
     spawn_cli_worker(cli_cmd_rx, cli_event_tx);
 
     let (mcp_command_queue, mcp_response_queue) = create_watchtower_mcp_queues();
-    start_watchtower_mcp_server(mcp_command_queue.clone(), mcp_response_queue.clone());
-
-    let (test_result_tx, test_result_rx) = mpsc::channel::<BackendEvent>();
+    let mcp_shutdown = Arc::new(AtomicBool::new(false));
+    start_watchtower_mcp_server(mcp_command_queue.clone(), mcp_response_queue.clone(), mcp_shutdown.clone());
+
+    let (test_result_tx, test_result_rx) = mpsc::sync_channel::<BackendEvent>(TEST_RESULT_CHANNEL_CAPACITY);
+    let test_result_tx = TestEventSender {
+        sender: test_result_tx,
+        dropped: Arc::new(AtomicUsize::new(0)),
+    };
+
+    let dev_mode = std::env::var_os("WATCHTOWER_DEV").is_some();
+    let port = if dev_mode {
+        serve_site_from_disk(site_dist_dir())
+    } else {
+        serve_embedded_dir(&DIST)
+    };
+
+    let config = load_config();
+    let effective_config = with_env_overrides(&config);
+    let scene = SceneState {
+        shadows_enabled: Some(effective_config.shadows_enabled),
+        ..Default::default()
+    };
 
     launch(Watchtower {
-        port: serve_embedded_dir(&DIST),
+        port,
+        dev_mode,
         ctx: WebviewContext::default(),
-        connected: false,
         cli_cmd_tx,
         cli_event_rx,
         mcp_command_queue,
         mcp_response_queue,
+        mcp_shutdown,
+        current_command_id: None,
         test_result_tx,
         test_result_rx,
         cli_prompt_test_running: Arc::new(AtomicBool::new(false)),
-        scene: SceneState::default(),
+        pending_permissions: HashMap::new(),
+        pending_mcp_input: None,
+        scene,
+        config,
         assemble_counter: 0,
+        layout_jitter: None,
+        effective_keyboard_camera: effective_config.keyboard_camera,
+        last_activity: Instant::now(),
+        idle_redraw_started: false,
+        current_tool_name: "",
+        current_not_undoable: false,
+        last_change: None,
+        change_log: VecDeque::new(),
+        sent_history_depth: None,
+        last_frame: Instant::now(),
+        effect_time: 0.0,
+        frame_stats: FrameStats::default(),
+        mcp_logger: McpLogger::from_env(),
+        mcp_replay: McpReplay::from_env(),
     })?;
 
     Ok(())
 }
 
+#[derive(Clone)]
+struct TestEventSender {
+    sender: mpsc::SyncSender<BackendEvent>,
+    dropped: Arc<AtomicUsize>,
+}
+
+impl TestEventSender {
+    fn send(&self, event: BackendEvent) {
+        if let Err(mpsc::TrySendError::Full(_)) = self.sender.try_send(event) {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+struct PendingPermission {
+    prompt: String,
+    input: serde_json::Value,
+}
+
 struct Watchtower {
     port: u16,
+    dev_mode: bool,
     ctx: WebviewContext<FrontendCommand, BackendEvent>,
-    connected: bool,
     cli_cmd_tx: mpsc::Sender<CliCommand>,
     cli_event_rx: mpsc::Receiver<CliEvent>,
     mcp_command_queue: WatchtowerCommandQueue,
     mcp_response_queue: WatchtowerResponseQueue,
-    test_result_tx: mpsc::Sender<BackendEvent>,
+    mcp_shutdown: Arc<AtomicBool>,
+    current_command_id: Option<u64>,
+    test_result_tx: TestEventSender,
     test_result_rx: mpsc::Receiver<BackendEvent>,
     cli_prompt_test_running: Arc<AtomicBool>,
+    pending_permissions: HashMap<String, PendingPermission>,
+    pending_mcp_input: Option<(String, Option<u64>)>,
     scene: SceneState,
+    config: Config,
     assemble_counter: u32,
+    layout_jitter: Option<LayoutJitter>,
+    effective_keyboard_camera: bool,
+    last_activity: Instant,
+    idle_redraw_started: bool,
+    current_tool_name: &'static str,
+    current_not_undoable: bool,
+    last_change: Option<String>,
+    change_log: VecDeque<String>,
+    sent_history_depth: Option<(usize, usize)>,
+    last_frame: Instant,
+    effect_time: f32,
+    frame_stats: FrameStats,
+    mcp_logger: McpLogger,
+    mcp_replay: Option<McpReplay>,
+}
+
+impl Drop for Watchtower {
+    fn drop(&mut self) {
+        self.mcp_shutdown.store(true, Ordering::SeqCst);
+    }
 }
 
 impl State for Watchtower {
@@ -88,47 +201,103 @@ impl State for Watchtower {
 
     fn ui(&mut self, world: &mut World, ctx: &egui::Context) {
         let commands: Vec<FrontendCommand> = self.ctx.drain_messages().collect();
+        let mut had_activity = !commands.is_empty();
         for cmd in commands {
             match cmd {
-                FrontendCommand::Ready => {
-                    if !self.connected {
-                        self.ctx.send(BackendEvent::Connected);
-                        self.ctx.send(BackendEvent::StatusUpdate {
-                            status: AgentStatus::Idle,
+                FrontendCommand::Ready { protocol_version } => {
+                    self.cancel_pending_mcp_input();
+                    self.sent_history_depth = None;
+                    if protocol_version != PROTOCOL_VERSION {
+                        self.ctx.send(BackendEvent::ProtocolMismatch {
+                            backend_version: PROTOCOL_VERSION,
+                            frontend_version: protocol_version,
+                        });
+                    }
+                    self.ctx.send(BackendEvent::Connected {
+                        mcp_port: MCP_PORT,
+                        version: env!("CARGO_PKG_VERSION").to_string(),
+                        capabilities: self.capabilities(),
+                        protocol_version: PROTOCOL_VERSION,
+                    });
+                    self.ctx.send(BackendEvent::StatusUpdate {
+                        status: AgentStatus::Idle,
+                    });
+                    self.ctx.send(BackendEvent::ConfigChanged {
+                        config: self.config.clone(),
+                    });
+                    for text in &self.change_log {
+                        self.ctx.send(BackendEvent::ChangeLog { text: text.clone() });
+                    }
+                    for (request_id, permission) in &self.pending_permissions {
+                        self.ctx.send(BackendEvent::UserInputRequest {
+                            request_id: request_id.clone(),
+                            prompt: permission.prompt.clone(),
+                            options: vec!["Allow".to_string(), "Deny".to_string()],
                         });
-                        self.connected = true;
                     }
                 }
-                FrontendCommand::SendPrompt { prompt, session_id, model } => {
+                FrontendCommand::SendPrompt { prompt, session_id, model, image_data_url } => {
+                    let image = match image_data_url.as_deref().map(parse_prompt_image) {
+                        Some(Ok(image)) => Some(image),
+                        Some(Err(message)) => {
+                            self.ctx.send(BackendEvent::Error { message });
+                            continue;
+                        }
+                        None => None,
+                    };
                     self.ctx.send(BackendEvent::StatusUpdate {
                         status: AgentStatus::Thinking,
                     });
                     let _ = self.cli_cmd_tx.send(CliCommand::StartQuery {
                         prompt,
                         session_id,
-                        model,
+                        model: model.or_else(|| with_env_overrides(&self.config).default_model),
+                        image,
                     });
                 }
                 FrontendCommand::CancelRequest => {
+                    self.pending_permissions.clear();
+                    self.cancel_pending_mcp_input();
                     let _ = self.cli_cmd_tx.send(CliCommand::Cancel);
                     self.ctx.send(BackendEvent::StatusUpdate {
                         status: AgentStatus::Idle,
                     });
                 }
-                FrontendCommand::UserInputResponse { response, .. } => {
-                    let mut resp_queue = self.mcp_response_queue.write().unwrap();
-                    *resp_queue = Some(McpResponse::UserInput(response));
+                FrontendCommand::UserInputResponse { request_id, response } => {
+                    if let Some(permission) = self.pending_permissions.remove(&request_id) {
+                        let _ = self.cli_cmd_tx.send(CliCommand::PermissionDecision {
+                            request_id,
+                            allow: response == "Allow",
+                            input: permission.input,
+                        });
+                        continue;
+                    }
+                    let Some((_, command_id)) = self.pending_mcp_input.take_if(|(pending_id, _)| *pending_id == request_id) else {
+                        continue;
+                    };
+                    self.send_mcp_response(command_id, McpResponse::UserInput(response));
                 }
                 FrontendCommand::RunTest { test_name } => {
-                    self.handle_run_test(&test_name);
+                    self.handle_run_test(world, &test_name);
                 }
-                FrontendCommand::Assemble => {
-                    self.handle_assemble(world);
+                FrontendCommand::Assemble { demo, seed } => {
+                    self.handle_assemble(world, demo.as_deref(), seed);
+                }
+                FrontendCommand::Undo | FrontendCommand::Redo => {
+                    if let Err(message) = self.step_history(world, matches!(cmd, FrontendCommand::Redo)) {
+                        self.ctx.send(BackendEvent::Error { message });
+                    }
+                }
+                FrontendCommand::SetConfig { config } => {
+                    if let Err(message) = self.update_config(world, config) {
+                        self.ctx.send(BackendEvent::Error { message });
+                    }
                 }
             }
         }
 
         for event in self.cli_event_rx.try_iter() {
+            had_activity = true;
             match event {
                 CliEvent::SessionStarted { session_id } => {
                     self.ctx.send(BackendEvent::StreamingStarted {
@@ -167,12 +336,33 @@ impl State for Watchtower {
                         status: AgentStatus::Streaming,
                     });
                 }
+                CliEvent::ToolResult { tool_id, content, is_error } => {
+                    self.ctx.send(BackendEvent::ToolUseResult {
+                        tool_id,
+                        content,
+                        is_error,
+                    });
+                }
+                CliEvent::PermissionRequest { request_id, tool, input } => {
+                    let input_text = serde_json::to_string_pretty(&input).unwrap_or_default();
+                    let prompt = format!("Claude wants to use {tool}:\n{input_text}");
+                    self.pending_permissions.insert(request_id.clone(), PendingPermission {
+                        prompt: prompt.clone(),
+                        input,
+                    });
+                    self.ctx.send(BackendEvent::UserInputRequest {
+                        request_id,
+                        prompt,
+                        options: vec!["Allow".to_string(), "Deny".to_string()],
+                    });
+                }
                 CliEvent::TurnComplete { session_id } => {
                     self.ctx.send(BackendEvent::TurnComplete {
                         session_id,
                     });
                 }
                 CliEvent::Complete { session_id, total_cost_usd, num_turns } => {
+                    self.pending_permissions.clear();
                     self.ctx.send(BackendEvent::RequestComplete {
                         session_id,
                         total_cost_usd,
@@ -191,6 +381,7 @@ impl State for Watchtower {
                     }
                 }
                 CliEvent::Error { message } => {
+                    self.pending_permissions.clear();
                     self.ctx.send(BackendEvent::Error { message: message.clone() });
                     self.ctx.send(BackendEvent::StatusUpdate {
                         status: AgentStatus::Idle,
@@ -207,18 +398,58 @@ impl State for Watchtower {
             }
         }
 
-        let mcp_commands: Vec<McpCommand> = {
+        let mcp_commands: Vec<QueuedCommand> = {
             let mut queue = self.mcp_command_queue.write().unwrap();
             queue.drain(..).collect()
         };
 
-        for command in mcp_commands {
-            self.handle_mcp_command(command, world);
+        had_activity |= !mcp_commands.is_empty();
+        for queued in mcp_commands {
+            self.current_command_id = Some(queued.id);
+            self.handle_mcp_command(queued.command, world);
+        }
+        self.current_command_id = None;
+
+        if let Some(replay) = self.mcp_replay.as_mut() {
+            let replayed = replay.due_commands();
+            let finished = replay.is_finished();
+            had_activity |= !replayed.is_empty();
+            for command in replayed {
+                self.handle_mcp_command(command, world);
+            }
+            if finished && let Some(replay) = self.mcp_replay.take() {
+                self.ctx.send(BackendEvent::Notification {
+                    title: "MCP replay finished".to_string(),
+                    body: format!("Replayed {} commands", replay.total()),
+                });
+            }
         }
 
         for test_event in self.test_result_rx.try_iter() {
+            had_activity = true;
             self.ctx.send(test_event);
         }
+        let dropped_test_events = self.test_result_tx.dropped.swap(0, Ordering::Relaxed);
+        if dropped_test_events > 0 {
+            self.ctx.send(BackendEvent::Notification {
+                title: "Test events dropped".to_string(),
+                body: format!("{dropped_test_events} test events were dropped because the result queue was full"),
+            });
+        }
+
+        let now = Instant::now();
+        let delta_seconds = now.duration_since(self.last_frame).as_secs_f32();
+        self.last_frame = now;
+        self.frame_stats.record_frame(delta_seconds, self.scene.entities.len());
+        self.update_scene_effects(world, delta_seconds);
+        self.update_keyboard_camera(world, delta_seconds);
+        self.scene.update_spawn_reveals(world, delta_seconds);
+        self.sync_history_depth();
+
+        if had_activity {
+            self.last_activity = Instant::now();
+        }
+        let needs_continuous_redraw = self.is_animating();
 
         egui::CentralPanel::default()
             .frame(egui::Frame::NONE)
@@ -229,16 +460,63 @@ impl State for Watchtower {
                         self.port,
                         ui.available_rect_before_wrap(),
                     );
-                    handle.request_redraw();
+                    if needs_continuous_redraw {
+                        handle.request_redraw();
+                    }
+                    if !self.idle_redraw_started {
+                        self.idle_redraw_started = true;
+                        let idle_handle = handle.clone();
+                        let shutdown = self.mcp_shutdown.clone();
+                        std::thread::spawn(move || {
+                            while !shutdown.load(Ordering::SeqCst) {
+                                std::thread::sleep(IDLE_REDRAW_INTERVAL);
+                                idle_handle.request_redraw();
+                            }
+                        });
+                    }
                 }
             });
     }
 }
 
 impl Watchtower {
+    fn is_animating(&self) -> bool {
+        (self.scene.is_open() && self.scene.is_animating()) || self.last_activity.elapsed() < ACTIVITY_REDRAW_GRACE
+    }
+
+    fn send_mcp_response(&self, command_id: Option<u64>, response: McpResponse) {
+        let Some(command_id) = command_id else {
+            return;
+        };
+        if let Some(slot) = self.mcp_response_queue.write().unwrap().get_mut(&command_id) {
+            *slot = Some(response);
+        }
+    }
+
     fn respond_success(&self, message: &str) {
-        let mut resp = self.mcp_response_queue.write().unwrap();
-        *resp = Some(McpResponse::Success(message.to_string()));
+        let message = if self.current_not_undoable {
+            format!("{message} (not undoable)")
+        } else {
+            message.to_string()
+        };
+        self.send_mcp_response(self.current_command_id, McpResponse::Success(message));
+    }
+
+    fn cancel_pending_mcp_input(&mut self) {
+        if let Some((_, command_id)) = self.pending_mcp_input.take() {
+            self.send_mcp_response(
+                command_id,
+                McpResponse::UserInput("The user cancelled the request without responding".to_string()),
+            );
+        }
+    }
+
+    fn respond_error(&mut self, message: &str) {
+        self.ctx.send(BackendEvent::ToolError {
+            tool_name: self.current_tool_name.to_string(),
+            message: message.to_string(),
+        });
+        self.send_mcp_response(self.current_command_id, McpResponse::Error(message.to_string()));
     }
 
     fn setup_scene(&mut self, world: &mut World, window_count: u32) {
@@ -257,6 +535,8 @@ impl Watchtower {
         self.scene.camera_entity = Some(camera);
         self.scene.sun_entity = Some(sun);
         self.scene.window_count = window_count;
+        self.scene.window_size = [800, 600];
+        self.scene.apply_shadow_settings(world);
 
         for window_index in 0..window_count {
             world.resources.secondary_windows.pending_spawns.push(WindowSpawnRequest {
@@ -269,15 +549,14 @@ impl Watchtower {
     }
 
     fn spawn_named(&mut self, world: &mut World, name: &str, shape: &str, position: [f32; 3], scale: [f32; 3]) {
-        let pos = nalgebra_glm::Vec3::new(position[0], position[1], position[2]);
-        let entity = match shape {
-            "cube" => spawn_cube_at(world, pos),
-            "sphere" => spawn_sphere_at(world, pos),
-            "cylinder" => spawn_cylinder_at(world, pos),
-            "cone" => spawn_cone_at(world, pos),
-            "torus" => spawn_torus_at(world, pos),
-            "plane" => spawn_plane_at(world, pos),
-            _ => return,
+        let mut pos = nalgebra_glm::Vec3::new(position[0], position[1], position[2]);
+        if let Some(jitter) = self.layout_jitter.as_mut() {
+            let [offset_x, offset_z] = jitter.offset();
+            pos.x += offset_x;
+            pos.z += offset_z;
+        }
+        let Some(entity) = spawn_shape_at(world, shape, pos) else {
+            return;
         };
 
         if scale != [1.0, 1.0, 1.0] {
@@ -288,22 +567,372 @@ impl Watchtower {
         }
 
         self.scene.entities.insert(name.to_string(), entity);
+        self.scene.shapes.insert(name.to_string(), shape.to_string());
+        self.scene.queue_spawn_reveal(world, name, entity);
+        self.frame_stats.record_spawn();
+    }
+
+    fn update_config(&mut self, world: &mut World, config: Config) -> Result<std::path::PathBuf, String> {
+        let path = save_config(&config)?;
+        self.config = config;
+        let effective = with_env_overrides(&self.config);
+        self.scene.shadows_enabled = Some(effective.shadows_enabled);
+        self.effective_keyboard_camera = effective.keyboard_camera;
+        self.scene.apply_shadow_settings(world);
+        self.ctx.send(BackendEvent::ConfigChanged { config: self.config.clone() });
+        Ok(path)
+    }
+
+    fn capabilities(&self) -> Vec<String> {
+        let mut capabilities: Vec<String> = CAPABILITIES.iter().map(|capability| capability.to_string()).collect();
+        if self.dev_mode {
+            capabilities.push("reload_site".to_string());
+        }
+        capabilities
+    }
+
+    fn log_change(&mut self, text: String) {
+        self.ctx.send(BackendEvent::ChangeLog { text: text.clone() });
+        if self.change_log.len() == MAX_REPLAYED_CHANGES {
+            self.change_log.pop_front();
+        }
+        self.change_log.push_back(text.clone());
+        self.last_change = Some(text);
+    }
+
+    fn history_targets(&self, command: &McpCommand) -> Option<Vec<String>> {
+        match command {
+            McpCommand::SpawnEntity { name, .. }
+            | McpCommand::ImportGltf { name, .. }
+            | McpCommand::RemoveEntity { name }
+            | McpCommand::MoveEntity { name, .. }
+            | McpCommand::RotateEntity { name, .. }
+            | McpCommand::ScaleEntity { name, .. }
+            | McpCommand::SetTransformMatrix { name, .. }
+            | McpCommand::SetOpacity { name, .. }
+            | McpCommand::SetTexture { name, .. }
+            | McpCommand::SetEmissivePulse { name, .. }
+            | McpCommand::SetOrbit { name, .. }
+            | McpCommand::SetVelocity { name, .. }
+            | McpCommand::SetBillboard { name, .. }
+            | McpCommand::SetTrail { name, .. }
+            | McpCommand::AddKeyframe { name, .. }
+            | McpCommand::ClearAnimations { name: Some(name) } => Some(vec![name.clone()]),
+            McpCommand::MoveEntities { names, .. }
+            | McpCommand::RotateEntities { names, .. }
+            | McpCommand::ScaleEntities { names, .. } => Some(names.clone()),
+            McpCommand::SpawnPrefab { prefab, prefix, .. } => find_prefab(prefab).map(|prefab| {
+                prefab.parts.iter().map(|part| format!("{prefix}_{}", part.suffix)).collect()
+            }),
+            McpCommand::ApplyMaterial { names, prefix, .. } => Some(self.scene.select_entities(names, prefix.as_deref())),
+            McpCommand::ClearScene | McpCommand::ClearAnimations { name: None } => {
+                Some(self.scene.entities.keys().cloned().collect())
+            }
+            McpCommand::Seek { .. } => Some(self.scene.keyframes.keys().cloned().collect()),
+            _ => None,
+        }
     }
 
-    fn handle_assemble(&mut self, world: &mut World) {
+    fn step_history(&mut self, world: &mut World, redo: bool) -> Result<String, String> {
+        let entry = if redo { self.scene.history.redo() } else { self.scene.history.undo() };
+        let Some(entry) = entry else {
+            let direction = if redo { "redo" } else { "undo" };
+            return Err(format!("nothing to {direction}"));
+        };
+        let label = entry.label.clone();
+        let snapshot = if redo { entry.after.clone() } else { entry.before.clone() };
+        let pulsing: Vec<String> = snapshot.iter()
+            .filter(|(name, _)| self.scene.emissive_pulses.contains_key(name))
+            .map(|(name, _)| name.clone())
+            .collect();
+        let report = restore_snapshot(&mut self.scene, world, &snapshot);
+        for name in &pulsing {
+            self.refresh_entity_material(world, name);
+        }
+
+        let verb = if redo { "Redid" } else { "Undid" };
+        self.log_change(format!("{verb}: {label}"));
+        let mut message = format!("{verb}: {label}");
+        if !report.unrecreatable.is_empty() {
+            message.push_str(&format!("; could not recreate entities: {}", report.unrecreatable.join(", ")));
+        }
+        if !report.locked.is_empty() {
+            message.push_str(&format!("; left locked entities unchanged: {}", report.locked.join(", ")));
+        }
+        Ok(message)
+    }
+
+    fn sync_history_depth(&mut self) {
+        let depth = (self.scene.history.undo_depth(), self.scene.history.redo_depth());
+        if self.sent_history_depth == Some(depth) {
+            return;
+        }
+        self.sent_history_depth = Some(depth);
+        self.ctx.send(BackendEvent::HistoryChanged {
+            undo_depth: depth.0,
+            redo_depth: depth.1,
+        });
+    }
+
+    fn refuse_if_locked(&mut self, name: &str) -> bool {
+        if self.scene.locked.contains(name) {
+            self.respond_error(&format!("entity '{name}' is locked"));
+            return true;
+        }
+        false
+    }
+
+    fn transform_group(
+        &mut self,
+        world: &mut World,
+        names: &[String],
+        verb: &str,
+        apply: impl Fn(&mut LocalTransform, nalgebra_glm::Vec3),
+    ) {
+        let mut entities = Vec::new();
+        let mut missing = Vec::new();
+        let mut locked = Vec::new();
+        for name in names {
+            if self.scene.locked.contains(name) {
+                locked.push(name.as_str());
+                continue;
+            }
+            match self.scene.entities.get(name) {
+                Some(&entity) if !entities.contains(&entity) => entities.push(entity),
+                Some(_) => {}
+                None => missing.push(name.as_str()),
+            }
+        }
+        if entities.is_empty() {
+            if locked.is_empty() {
+                self.respond_error(&format!("none of the entities were found: {}", missing.join(", ")));
+            } else {
+                self.respond_error(&format!("no unlocked entities to change; locked: {}", locked.join(", ")));
+            }
+            return;
+        }
+
+        let mut center = nalgebra_glm::Vec3::zeros();
+        for &entity in &entities {
+            if let Some(transform) = world.get_local_transform(entity) {
+                center += transform.translation;
+            }
+        }
+        center /= entities.len() as f32;
+
+        for &entity in &entities {
+            if let Some(transform) = world.get_local_transform_mut(entity) {
+                apply(transform, center);
+            }
+            world.set_local_transform_dirty(entity, LocalTransformDirty);
+        }
+
+        self.log_change(format!("{verb} {} entities together", entities.len()));
+        let mut message = format!("{verb} {} entities", entities.len());
+        if !missing.is_empty() {
+            message.push_str(&format!("; not found: {}", missing.join(", ")));
+        }
+        if !locked.is_empty() {
+            message.push_str(&format!("; skipped locked: {}", locked.join(", ")));
+        }
+        self.respond_success(&message);
+    }
+
+    fn apply_material(&mut self, world: &mut World, name: &str, overrides: &MaterialOverrides) {
+        let Some(&entity) = self.scene.entities.get(name) else {
+            return;
+        };
+        write_entity_material(world, entity, name, overrides);
+        self.scene.materials.insert(name.to_string(), overrides.clone());
+    }
+
+    fn update_scene_effects(&mut self, world: &mut World, delta_seconds: f32) {
+        self.effect_time += delta_seconds;
+
+        let mut finished = Vec::new();
+        for (name, remaining) in &mut self.scene.highlights {
+            *remaining -= delta_seconds;
+            if *remaining <= 0.0 {
+                finished.push(name.clone());
+            }
+        }
+        for name in &finished {
+            self.scene.highlights.remove(name);
+        }
+
+        for (name, orbit) in &mut self.scene.orbits {
+            if self.scene.locked.contains(name) {
+                continue;
+            }
+            let Some(&entity) = self.scene.entities.get(name) else {
+                continue;
+            };
+            let position = orbit.advance(delta_seconds);
+            if let Some(transform) = world.get_local_transform_mut(entity) {
+                transform.translation = position;
+            }
+            world.set_local_transform_dirty(entity, LocalTransformDirty);
+        }
+
+        for (name, velocity) in &mut self.scene.velocities {
+            if self.scene.locked.contains(name) {
+                continue;
+            }
+            let Some(&entity) = self.scene.entities.get(name) else {
+                continue;
+            };
+            let shape = self.scene.shapes.get(name).map(String::as_str).unwrap_or("cube");
+            let Some(transform) = world.get_local_transform_mut(entity) else {
+                continue;
+            };
+            let ground_height = (shape_half_extents(shape).y * transform.scale.y).abs();
+            velocity.advance(&mut transform.translation, ground_height, delta_seconds);
+            world.set_local_transform_dirty(entity, LocalTransformDirty);
+        }
+
+        if let Some(follow) = &self.scene.camera_follow
+            && let Some(camera) = self.scene.camera_entity
+            && let Some(target) = self.scene.entities.get(&follow.target)
+                .and_then(|&entity| world.get_local_transform(entity))
+                .map(|transform| transform.translation + follow.offset)
+            && let Some(pan_orbit) = world.get_pan_orbit_camera_mut(camera)
+        {
+            let blend = 1.0 - (-follow.smoothing * delta_seconds).exp();
+            pan_orbit.target_focus += (target - pan_orbit.target_focus) * blend;
+        }
+
+        let camera_rotation = self.scene.camera_entity
+            .and_then(|camera| world.get_local_transform(camera))
+            .map(|transform| transform.rotation);
+        if let Some(camera_rotation) = camera_rotation {
+            for name in self.scene.billboards.difference(&self.scene.locked) {
+                let Some(&entity) = self.scene.entities.get(name) else {
+                    continue;
+                };
+                if let Some(transform) = world.get_local_transform_mut(entity) {
+                    transform.rotation = camera_rotation;
+                }
+                world.set_local_transform_dirty(entity, LocalTransformDirty);
+            }
+        }
+
+        self.scene.update_trails(world);
+
+        let mut animated: Vec<String> = self.scene.highlights.keys()
+            .chain(self.scene.emissive_pulses.keys())
+            .chain(finished.iter())
+            .cloned()
+            .collect();
+        animated.sort();
+        animated.dedup();
+        for name in animated {
+            self.refresh_entity_material(world, &name);
+        }
+    }
+
+    fn update_keyboard_camera(&mut self, world: &mut World, delta_seconds: f32) {
+        if !self.effective_keyboard_camera {
+            return;
+        }
+        let Some(camera) = self.scene.camera_entity else {
+            return;
+        };
+        let Some(window_state) = world.resources.secondary_windows.states.iter().find(|state| state.focused) else {
+            return;
+        };
+        let keyboard = &window_state.input.keyboard;
+        let axis = |positive: KeyCode, negative: KeyCode| {
+            keyboard.is_key_pressed(positive) as i32 as f32 - keyboard.is_key_pressed(negative) as i32 as f32
+        };
+        let forward_input = axis(KeyCode::KeyW, KeyCode::KeyS);
+        let right_input = axis(KeyCode::KeyD, KeyCode::KeyA);
+        let zoom_input = axis(KeyCode::KeyE, KeyCode::KeyQ);
+        if forward_input == 0.0 && right_input == 0.0 && zoom_input == 0.0 {
+            return;
+        }
+
+        let Some(pan_orbit) = world.get_pan_orbit_camera_mut(camera) else {
+            return;
+        };
+        let yaw = pan_orbit.target_yaw;
+        let forward = nalgebra_glm::Vec3::new(-yaw.sin(), 0.0, -yaw.cos());
+        let right = nalgebra_glm::Vec3::new(yaw.cos(), 0.0, -yaw.sin());
+        let pan_distance = pan_orbit.target_radius * KEYBOARD_PAN_SPEED * delta_seconds;
+        pan_orbit.target_focus += (forward * forward_input + right * right_input) * pan_distance;
+        pan_orbit.target_radius = (pan_orbit.target_radius * (1.0 - zoom_input * KEYBOARD_ZOOM_SPEED * delta_seconds)).max(0.5);
+        self.last_activity = Instant::now();
+    }
+
+    fn refresh_entity_material(&mut self, world: &mut World, name: &str) {
+        let Some(&entity) = self.scene.entities.get(name) else {
+            return;
+        };
+        let mut overrides = self.scene.materials.get(name).cloned().unwrap_or_default();
+        let mut emissive = overrides.emissive.unwrap_or([0.0, 0.0, 0.0]);
+        let mut glowing = false;
+
+        if self.scene.highlights.contains_key(name) {
+            let pulse = 0.5 + 0.5 * (self.effect_time * HIGHLIGHT_PULSE_SPEED).sin();
+            for (channel, glow) in emissive.iter_mut().zip(HIGHLIGHT_EMISSIVE) {
+                *channel += glow * pulse;
+            }
+            glowing = true;
+        }
+        if let Some(emissive_pulse) = self.scene.emissive_pulses.get(name) {
+            let pulse = 0.5 + 0.5 * (self.effect_time * emissive_pulse.speed * std::f32::consts::TAU).sin();
+            for (channel, glow) in emissive.iter_mut().zip(emissive_pulse.color) {
+                *channel += glow * pulse;
+            }
+            glowing = true;
+        }
+
+        if glowing {
+            overrides.emissive = Some(emissive);
+        }
+        write_entity_material(world, entity, name, &overrides);
+    }
+
+    fn handle_assemble(&mut self, world: &mut World, demo: Option<&str>, seed: Option<u64>) {
+        let index = match demo {
+            Some(name) => match ASSEMBLE_DEMOS.iter().position(|demo| *demo == name) {
+                Some(index) => index,
+                None => {
+                    self.ctx.send(BackendEvent::Error {
+                        message: format!("unknown demo '{name}'. Use: {}", ASSEMBLE_DEMOS.join(", ")),
+                    });
+                    return;
+                }
+            },
+            None => {
+                let index = self.assemble_counter as usize % ASSEMBLE_DEMOS.len();
+                self.assemble_counter += 1;
+                index
+            }
+        };
+
+        let preserve_camera = with_env_overrides(&self.config).preserve_camera_on_assemble;
+        let preserved_pose = if preserve_camera && self.scene.camera_touched(world) {
+            self.scene.camera_pose(world)
+        } else {
+            None
+        };
+
         if self.scene.is_open() {
             self.scene.teardown(world);
         }
 
-        let config = self.assemble_counter % 4;
-        self.assemble_counter += 1;
-
-        match config {
-            0 => self.assemble_cityscape(world),
-            1 => self.assemble_solar_system(world),
-            2 => self.assemble_garden(world),
+        self.layout_jitter = seed.map(LayoutJitter::new);
+        match ASSEMBLE_DEMOS[index] {
+            "cityscape" => self.assemble_cityscape(world),
+            "solar_system" => self.assemble_solar_system(world),
+            "garden" => self.assemble_garden(world),
             _ => self.assemble_abstract(world),
         }
+        self.layout_jitter = None;
+        self.scene.frame_camera(world);
+        if let Some(pose) = preserved_pose {
+            self.scene.set_camera_pose(world, pose);
+        }
     }
 
     fn assemble_cityscape(&mut self, world: &mut World) {
@@ -414,6 +1043,26 @@ impl Watchtower {
     }
 
     fn handle_mcp_command(&mut self, command: McpCommand, world: &mut World) {
+        if self.current_command_id.is_some() && !matches!(command, McpCommand::ReportToolError { .. }) {
+            self.mcp_logger.log(&command);
+        }
+        self.current_tool_name = command.tool_name();
+        self.current_not_undoable = command.is_untracked_scene_change();
+        self.last_change = None;
+        let tracked = self.history_targets(&command);
+        let before = tracked.as_ref().map(|names| snapshot_entities(&self.scene, world, names));
+        self.apply_mcp_command(command, world);
+
+        if let (Some(names), Some(before)) = (tracked, before) {
+            let after = snapshot_entities(&self.scene, world, &names);
+            if after != before {
+                let label = self.last_change.take().unwrap_or_else(|| self.current_tool_name.to_string());
+                self.scene.history.record(HistoryEntry { label, before, after });
+            }
+        }
+    }
+
+    fn apply_mcp_command(&mut self, command: McpCommand, world: &mut World) {
         match command {
             McpCommand::ShowNotification { title, body } => {
                 self.ctx.send(BackendEvent::Notification { title, body });
@@ -423,6 +1072,8 @@ impl Watchtower {
                 let content_format = match format.as_str() {
                     "markdown" => ContentFormat::Markdown,
                     "code" => ContentFormat::Code,
+                    "table" => ContentFormat::Table,
+                    "diff" => ContentFormat::Diff,
                     _ => ContentFormat::Text,
                 };
                 self.ctx.send(BackendEvent::ContentDisplay {
@@ -432,6 +1083,13 @@ impl Watchtower {
                 self.respond_success("Content displayed");
             }
             McpCommand::RequestUserInput { request_id, prompt, options } => {
+                if let Some((_, command_id)) = self.pending_mcp_input.take() {
+                    self.send_mcp_response(
+                        command_id,
+                        McpResponse::UserInput("A newer request_user_input call replaced this one before the user responded".to_string()),
+                    );
+                }
+                self.pending_mcp_input = Some((request_id.clone(), self.current_command_id));
                 self.ctx.send(BackendEvent::UserInputRequest {
                     request_id,
                     prompt,
@@ -471,8 +1129,11 @@ impl Watchtower {
                 let sun = spawn_sun(world);
 
                 self.scene.window_count = 1;
+                self.scene.window_size = [width, height];
                 self.scene.camera_entity = Some(camera);
                 self.scene.sun_entity = Some(sun);
+                self.scene.apply_shadow_settings(world);
+                self.scene.frame_camera(world);
 
                 self.respond_success("3D window opened with camera and sun");
             }
@@ -485,72 +1146,605 @@ impl Watchtower {
                 self.scene.teardown(world);
                 self.respond_success("3D window closed");
             }
-            McpCommand::SpawnEntity { name, shape, position, scale } => {
+            McpCommand::SpawnEntity { name, shape, position, scale, material } => {
                 if !self.scene.is_open() {
-                    self.respond_success("Error: 3D window is not open");
+                    self.respond_error("3D window is not open");
                     return;
                 }
                 if self.scene.entities.contains_key(&name) {
-                    self.respond_success(&format!("Error: entity '{name}' already exists"));
+                    self.respond_error(&format!("entity '{name}' already exists"));
                     return;
                 }
 
-                let valid_shapes = ["cube", "sphere", "cylinder", "cone", "torus", "plane"];
-                if !valid_shapes.contains(&shape.as_str()) {
-                    self.respond_success(&format!("Error: unknown shape '{shape}'. Use: cube, sphere, cylinder, cone, torus, plane"));
+                if !SHAPES.contains(&shape.as_str()) {
+                    self.respond_error(&format!("unknown shape '{shape}'. Use: {}", SHAPES.join(", ")));
                     return;
                 }
 
                 self.spawn_named(world, &name, &shape, position, scale);
+                if !material.is_empty() {
+                    self.apply_material(world, &name, &material);
+                }
+                self.log_change(format!("Added a {shape} named '{name}' at [{}, {}, {}]", position[0], position[1], position[2]));
                 self.respond_success(&format!("Spawned {shape} entity '{name}'"));
             }
+            McpCommand::ImportGltf { name, path, position, scale } => {
+                if !self.scene.is_open() {
+                    self.respond_error("3D window is not open");
+                    return;
+                }
+                if self.scene.entities.contains_key(&name) {
+                    self.respond_error(&format!("entity '{name}' already exists"));
+                    return;
+                }
+                let imported = match spawn_gltf_at(world, &path, nalgebra_glm::Vec3::new(position[0], position[1], position[2])) {
+                    Ok(imported) => imported,
+                    Err(error) => {
+                        self.respond_error(&error);
+                        return;
+                    }
+                };
+                let entity = imported.entity;
+                if let Some(transform) = world.get_local_transform_mut(entity) {
+                    transform.scale = nalgebra_glm::Vec3::new(scale[0], scale[1], scale[2]);
+                }
+                world.set_local_transform_dirty(entity, LocalTransformDirty);
+                self.scene.entities.insert(name.clone(), entity);
+                self.scene.gltf_sources.insert(name.clone(), path.clone());
+                self.scene.queue_spawn_reveal(world, &name, entity);
+                self.frame_stats.record_spawn();
+
+                self.log_change(format!("Imported the model '{path}' as '{name}'"));
+                self.respond_success(&format!(
+                    "Imported '{path}' as entity '{name}' ({} meshes, {} textures)",
+                    imported.meshes,
+                    imported.textures
+                ));
+            }
+            McpCommand::SpawnPrefab { prefab, prefix, position, scale } => {
+                if !self.scene.is_open() {
+                    self.respond_error("3D window is not open");
+                    return;
+                }
+                let Some(prefab_definition) = find_prefab(&prefab) else {
+                    let names: Vec<&str> = PREFABS.iter().map(|prefab| prefab.name).collect();
+                    self.respond_error(&format!("unknown prefab '{prefab}'. Use: {}", names.join(", ")));
+                    return;
+                };
+
+                let part_names: Vec<String> = prefab_definition.parts.iter()
+                    .map(|part| format!("{prefix}_{}", part.suffix))
+                    .collect();
+                if let Some(existing) = part_names.iter().find(|name| self.scene.entities.contains_key(*name)) {
+                    self.respond_error(&format!("entity '{existing}' already exists; choose a different prefix"));
+                    return;
+                }
+
+                for (part, part_name) in prefab_definition.parts.iter().zip(&part_names) {
+                    let part_position = [
+                        position[0] + part.offset[0] * scale,
+                        position[1] + part.offset[1] * scale,
+                        position[2] + part.offset[2] * scale,
+                    ];
+                    let part_scale = [part.scale[0] * scale, part.scale[1] * scale, part.scale[2] * scale];
+                    self.spawn_named(world, part_name, part.shape, part_position, part_scale);
+                    self.apply_material(world, part_name, &MaterialOverrides {
+                        color: Some(part.color),
+                        ..Default::default()
+                    });
+                }
+
+                self.log_change(format!("Built a {prefab} from {} parts named '{prefix}_*'", part_names.len()));
+                self.respond_success(&format!("Spawned {prefab} prefab: {}", part_names.join(", ")));
+            }
+            McpCommand::HighlightEntity { name, duration_secs } => {
+                if !self.scene.entities.contains_key(&name) {
+                    self.respond_error(&format!("entity '{name}' not found"));
+                    return;
+                }
+                let duration_secs = duration_secs.clamp(0.0, MAX_HIGHLIGHT_SECONDS);
+                self.scene.highlights.insert(name.clone(), duration_secs);
+                self.respond_success(&format!("Highlighting entity '{name}' for {duration_secs} seconds"));
+            }
+            McpCommand::SetOpacity { name, alpha } => {
+                if !self.scene.entities.contains_key(&name) {
+                    self.respond_error(&format!("entity '{name}' not found"));
+                    return;
+                }
+                if !alpha.is_finite() {
+                    self.respond_error("alpha must be a number between 0 and 1");
+                    return;
+                }
+                let alpha = alpha.clamp(0.0, 1.0);
+                let mut overrides = self.scene.materials.get(&name).cloned().unwrap_or_default();
+                let mut color = overrides.color.unwrap_or([1.0, 1.0, 1.0, 1.0]);
+                color[3] = alpha;
+                overrides.color = Some(color);
+                self.apply_material(world, &name, &overrides);
+                let mode = if alpha < 1.0 { "blended" } else { "opaque" };
+                self.log_change(format!("Made '{name}' {:.0}% opaque", alpha * 100.0));
+                self.respond_success(&format!("Set opacity of '{name}' to {alpha} ({mode})"));
+            }
+            McpCommand::SetTexture { name, texture_path } => {
+                if !self.scene.entities.contains_key(&name) {
+                    self.respond_error(&format!("entity '{name}' not found"));
+                    return;
+                }
+                let (rgba_data, width, height) = match load_texture_file(std::path::Path::new(&texture_path)) {
+                    Ok(texture) => texture,
+                    Err(message) => {
+                        self.respond_error(&message);
+                        return;
+                    }
+                };
+                let texture_name = format!("watchtower_texture_{name}");
+                world.queue_command(WorldCommand::LoadTexture {
+                    name: texture_name.clone(),
+                    rgba_data,
+                    width,
+                    height,
+                });
+                let mut overrides = self.scene.materials.get(&name).cloned().unwrap_or_default();
+                overrides.texture = Some(texture_name);
+                self.apply_material(world, &name, &overrides);
+                self.log_change(format!("Painted '{name}' with the image '{texture_path}'"));
+                self.respond_success(&format!("Applied {width}x{height} texture '{texture_path}' to '{name}'"));
+            }
+            McpCommand::SetEmissivePulse { name, color, speed, enabled } => {
+                if !self.scene.entities.contains_key(&name) {
+                    self.respond_error(&format!("entity '{name}' not found"));
+                    return;
+                }
+                if !enabled {
+                    self.scene.emissive_pulses.remove(&name);
+                    self.refresh_entity_material(world, &name);
+                    self.log_change(format!("Stopped '{name}' glowing"));
+                    self.respond_success(&format!("Stopped emissive pulse on '{name}'"));
+                    return;
+                }
+                self.scene.emissive_pulses.insert(name.clone(), EmissivePulse { color, speed });
+                self.log_change(format!("Made '{name}' pulse with light"));
+                self.respond_success(&format!(
+                    "Pulsing '{name}' with emissive [{}, {}, {}] at {speed} cycles per second",
+                    color[0], color[1], color[2]
+                ));
+            }
+            McpCommand::SetOrbit { name, center, radius, speed_deg_per_sec, axis } => {
+                if self.refuse_if_locked(&name) {
+                    return;
+                }
+                let Some(&entity) = self.scene.entities.get(&name) else {
+                    self.respond_error(&format!("entity '{name}' not found"));
+                    return;
+                };
+                if speed_deg_per_sec == 0.0 {
+                    let message = if self.scene.orbits.remove(&name).is_some() {
+                        self.log_change(format!("Stopped '{name}' orbiting"));
+                        format!("Stopped orbit of '{name}'")
+                    } else {
+                        format!("Entity '{name}' was not orbiting")
+                    };
+                    self.respond_success(&message);
+                    return;
+                }
+                let start = world.get_local_transform(entity)
+                    .map(|transform| transform.translation)
+                    .unwrap_or_else(nalgebra_glm::Vec3::zeros);
+                let center = nalgebra_glm::Vec3::new(center[0], center[1], center[2]);
+                let axis = nalgebra_glm::Vec3::new(axis[0], axis[1], axis[2]);
+                let Some(orbit) = Orbit::new(center, axis, start, radius, speed_deg_per_sec) else {
+                    self.respond_error("axis must be non-zero");
+                    return;
+                };
+                self.scene.orbits.insert(name.clone(), orbit);
+                self.log_change(format!(
+                    "Set '{name}' circling [{}, {}, {}] at radius {radius}",
+                    center.x, center.y, center.z
+                ));
+                self.respond_success(&format!(
+                    "'{name}' orbiting [{}, {}, {}] at radius {radius}, {speed_deg_per_sec} deg/s",
+                    center.x, center.y, center.z
+                ));
+            }
+            McpCommand::SetVelocity { name, velocity, gravity, bounciness } => {
+                if self.refuse_if_locked(&name) {
+                    return;
+                }
+                if !self.scene.entities.contains_key(&name) {
+                    self.respond_error(&format!("entity '{name}' not found"));
+                    return;
+                }
+                let linear = nalgebra_glm::Vec3::new(velocity[0], velocity[1], velocity[2]);
+                if !gravity && linear == nalgebra_glm::Vec3::zeros() {
+                    self.scene.velocities.remove(&name);
+                    self.log_change(format!("Stopped '{name}' moving"));
+                    self.respond_success(&format!("Stopped '{name}'"));
+                    return;
+                }
+                self.scene.velocities.insert(name.clone(), Velocity {
+                    linear,
+                    gravity,
+                    bounciness: bounciness.clamp(0.0, 1.0),
+                });
+                let falling = if gravity { " under gravity" } else { "" };
+                self.log_change(format!("Set '{name}' moving{falling}"));
+                self.respond_success(&format!(
+                    "'{name}' moving at [{}, {}, {}] units/s{falling}",
+                    velocity[0], velocity[1], velocity[2]
+                ));
+            }
+            McpCommand::SetBillboard { name, enabled } => {
+                if self.refuse_if_locked(&name) {
+                    return;
+                }
+                if !self.scene.entities.contains_key(&name) {
+                    self.respond_error(&format!("entity '{name}' not found"));
+                    return;
+                }
+                if enabled {
+                    self.scene.billboards.insert(name.clone());
+                    self.log_change(format!("Made '{name}' always face the camera"));
+                    self.respond_success(&format!("'{name}' now faces the camera"));
+                } else {
+                    self.scene.billboards.remove(&name);
+                    self.log_change(format!("Stopped '{name}' facing the camera"));
+                    self.respond_success(&format!("'{name}' no longer faces the camera"));
+                }
+            }
+            McpCommand::SetTrail { name, length, color, enabled } => {
+                if !self.scene.entities.contains_key(&name) {
+                    self.respond_error(&format!("entity '{name}' not found"));
+                    return;
+                }
+                if !enabled {
+                    match self.scene.trails.remove(&name) {
+                        Some(trail) => {
+                            trail.despawn(world);
+                            self.log_change(format!("Removed the trail behind '{name}'"));
+                            self.respond_success(&format!("Removed the trail behind '{name}'"));
+                        }
+                        None => self.respond_success(&format!("'{name}' has no trail")),
+                    }
+                    return;
+                }
+                let trail = Trail::new(length, color);
+                let length = trail.length;
+                if let Some(previous) = self.scene.trails.insert(name.clone(), trail) {
+                    previous.despawn(world);
+                }
+                self.log_change(format!("Added a trail behind '{name}'"));
+                self.respond_success(&format!("'{name}' now leaves a {length}-segment trail"));
+            }
+            McpCommand::AddKeyframe { name, time, position, rotation, scale } => {
+                if !time.is_finite() || time < 0.0 {
+                    self.respond_error("time must be a non-negative number of seconds");
+                    return;
+                }
+                if self.refuse_if_locked(&name) {
+                    return;
+                }
+                let Some(transform) = self.scene.entities.get(&name).and_then(|&entity| world.get_local_transform(entity)) else {
+                    self.respond_error(&format!("entity '{name}' not found"));
+                    return;
+                };
+                let keyframe = Keyframe {
+                    time,
+                    translation: position.map(|position| nalgebra_glm::Vec3::new(position[0], position[1], position[2]))
+                        .unwrap_or(transform.translation),
+                    rotation: rotation.map(euler_degrees_to_quat).unwrap_or(transform.rotation),
+                    scale: scale.map(|scale| nalgebra_glm::Vec3::new(scale[0], scale[1], scale[2]))
+                        .unwrap_or(transform.scale),
+                };
+                let count = self.scene.insert_keyframe(&name, keyframe);
+                self.log_change(format!("Added a keyframe for '{name}' at {time}s"));
+                self.respond_success(&format!("Keyframe at {time}s added to '{name}' ({count} keyframes)"));
+            }
+            McpCommand::Seek { time } => {
+                let mut posed = Vec::new();
+                let mut locked = Vec::new();
+                for (name, keyframes) in &self.scene.keyframes {
+                    if self.scene.locked.contains(name) {
+                        locked.push(name.clone());
+                        continue;
+                    }
+                    let (Some(&entity), Some(pose)) = (self.scene.entities.get(name), sample_keyframes(keyframes, time)) else {
+                        continue;
+                    };
+                    if let Some(transform) = world.get_local_transform_mut(entity) {
+                        transform.translation = pose.translation;
+                        transform.rotation = pose.rotation;
+                        transform.scale = pose.scale;
+                    }
+                    world.set_local_transform_dirty(entity, LocalTransformDirty);
+                    posed.push(name.clone());
+                }
+                if posed.is_empty() && locked.is_empty() {
+                    self.respond_error("no entities have keyframes; use add_keyframe first");
+                    return;
+                }
+                if !posed.is_empty() {
+                    self.log_change(format!("Scrubbed the timeline to {time}s"));
+                }
+                let mut message = format!("Posed {} entities at {time}s", posed.len());
+                if !locked.is_empty() {
+                    locked.sort();
+                    message.push_str(&format!("; skipped locked entities: {}", locked.join(", ")));
+                }
+                self.respond_success(&message);
+            }
+            McpCommand::ListAnimations => {
+                let scene = &self.scene;
+                let mut orbits: Vec<_> = scene.orbits.iter().map(|(name, orbit)| serde_json::json!({
+                    "name": name,
+                    "center": [orbit.center.x, orbit.center.y, orbit.center.z],
+                    "axis": [orbit.axis.x, orbit.axis.y, orbit.axis.z],
+                    "radius": orbit.radius,
+                    "speed_deg_per_sec": orbit.speed_deg_per_sec,
+                })).collect();
+                let mut pulses: Vec<_> = scene.emissive_pulses.iter().map(|(name, pulse)| serde_json::json!({
+                    "name": name,
+                    "color": pulse.color,
+                    "speed": pulse.speed,
+                })).collect();
+                let mut velocities: Vec<_> = scene.velocities.iter().map(|(name, velocity)| serde_json::json!({
+                    "name": name,
+                    "velocity": [velocity.linear.x, velocity.linear.y, velocity.linear.z],
+                    "gravity": velocity.gravity,
+                    "bounciness": velocity.bounciness,
+                })).collect();
+                let mut highlights: Vec<_> = scene.highlights.iter().map(|(name, remaining)| serde_json::json!({
+                    "name": name,
+                    "remaining_secs": remaining,
+                })).collect();
+                let mut trails: Vec<_> = scene.trails.iter().map(|(name, trail)| serde_json::json!({
+                    "name": name,
+                    "length": trail.length,
+                    "color": trail.color,
+                })).collect();
+                let mut keyframes: Vec<_> = scene.keyframes.iter().map(|(name, keyframes)| serde_json::json!({
+                    "name": name,
+                    "times": keyframes.iter().map(|keyframe| keyframe.time).collect::<Vec<_>>(),
+                })).collect();
+                let mut billboards: Vec<&String> = scene.billboards.iter().collect();
+                billboards.sort();
+                for list in [&mut orbits, &mut pulses, &mut velocities, &mut highlights, &mut trails, &mut keyframes] {
+                    list.sort_by(|first, second| first["name"].as_str().cmp(&second["name"].as_str()));
+                }
+                let json = serde_json::json!({
+                    "orbits": orbits,
+                    "emissive_pulses": pulses,
+                    "velocities": velocities,
+                    "billboards": billboards,
+                    "highlights": highlights,
+                    "trails": trails,
+                    "keyframes": keyframes,
+                });
+                self.respond_success(&serde_json::to_string_pretty(&json).unwrap_or_default());
+            }
+            McpCommand::ClearAnimations { name } => {
+                let names: Vec<String> = match name {
+                    Some(name) if !self.scene.entities.contains_key(&name) => {
+                        self.respond_error(&format!("entity '{name}' not found"));
+                        return;
+                    }
+                    Some(name) => vec![name],
+                    None => self.scene.orbits.keys()
+                        .chain(self.scene.emissive_pulses.keys())
+                        .chain(self.scene.velocities.keys())
+                        .chain(self.scene.billboards.iter())
+                        .chain(self.scene.highlights.keys())
+                        .chain(self.scene.trails.keys())
+                        .chain(self.scene.keyframes.keys())
+                        .cloned()
+                        .collect(),
+                };
+                let mut stopped = 0;
+                for name in &names {
+                    let material_animated = self.scene.emissive_pulses.remove(name).is_some()
+                        | self.scene.highlights.remove(name).is_some();
+                    let moving = self.scene.orbits.remove(name).is_some()
+                        | self.scene.velocities.remove(name).is_some()
+                        | self.scene.billboards.remove(name)
+                        | self.scene.keyframes.remove(name).is_some();
+                    let trailing = match self.scene.trails.remove(name) {
+                        Some(trail) => {
+                            trail.despawn(world);
+                            true
+                        }
+                        None => false,
+                    };
+                    if material_animated {
+                        self.refresh_entity_material(world, name);
+                    }
+                    if material_animated || moving || trailing {
+                        stopped += 1;
+                    }
+                }
+                if stopped > 0 {
+                    self.log_change(format!("Stopped animations on {stopped} entities"));
+                }
+                self.respond_success(&format!("Stopped animations on {stopped} entities"));
+            }
+            McpCommand::GetConfig => {
+                let json = serde_json::json!({
+                    "path": config::config_path(),
+                    "saved": self.config,
+                    "effective": with_env_overrides(&self.config),
+                });
+                self.respond_success(&serde_json::to_string_pretty(&json).unwrap_or_default());
+            }
+            McpCommand::SetConfig { default_model, cli_test_timeout_secs, shadows_enabled, keyboard_camera, preserve_camera_on_assemble } => {
+                let mut config = self.config.clone();
+                if let Some(model) = default_model {
+                    config.default_model = if model.trim().is_empty() { None } else { Some(model) };
+                }
+                if let Some(timeout) = cli_test_timeout_secs {
+                    config.cli_test_timeout_secs = timeout;
+                }
+                if let Some(enabled) = shadows_enabled {
+                    config.shadows_enabled = enabled;
+                }
+                if let Some(enabled) = keyboard_camera {
+                    config.keyboard_camera = enabled;
+                }
+                if let Some(enabled) = preserve_camera_on_assemble {
+                    config.preserve_camera_on_assemble = enabled;
+                }
+                match self.update_config(world, config) {
+                    Ok(path) => self.respond_success(&format!("Config saved to {}", path.display())),
+                    Err(message) => self.respond_error(&message),
+                }
+            }
             McpCommand::RemoveEntity { name } => {
                 if let Some(entity) = self.scene.entities.remove(&name) {
                     despawn_recursive_immediate(world, entity);
+                    self.scene.forget_entity(&name);
+                    self.log_change(format!("Removed '{name}'"));
                     self.respond_success(&format!("Removed entity '{name}'"));
                 } else {
-                    self.respond_success(&format!("Error: entity '{name}' not found"));
+                    self.respond_error(&format!("entity '{name}' not found"));
                 }
             }
             McpCommand::MoveEntity { name, position } => {
+                if self.refuse_if_locked(&name) {
+                    return;
+                }
                 if let Some(&entity) = self.scene.entities.get(&name) {
+                    let target = nalgebra_glm::Vec3::new(position[0], position[1], position[2]);
+                    let mut offset = nalgebra_glm::Vec3::zeros();
                     if let Some(transform) = world.get_local_transform_mut(entity) {
-                        transform.translation = nalgebra_glm::Vec3::new(position[0], position[1], position[2]);
+                        offset = target - transform.translation;
+                        transform.translation = target;
                     }
                     world.set_local_transform_dirty(entity, LocalTransformDirty);
+                    self.log_change(format!("Moved '{name}' {}", describe_offset(offset)));
                     self.respond_success(&format!("Moved entity '{name}' to [{}, {}, {}]", position[0], position[1], position[2]));
                 } else {
-                    self.respond_success(&format!("Error: entity '{name}' not found"));
+                    self.respond_error(&format!("entity '{name}' not found"));
                 }
             }
-            McpCommand::RotateEntity { name, rotation } => {
+            McpCommand::RotateEntity { name, rotation, snap_degrees } => {
+                if self.refuse_if_locked(&name) {
+                    return;
+                }
+                let rotation = match snap_degrees {
+                    Some(step) if step > 0.0 => rotation.map(|angle| (angle / step).round() * step),
+                    _ => rotation,
+                };
                 if let Some(&entity) = self.scene.entities.get(&name) {
-                    let radians_x = rotation[0].to_radians();
-                    let radians_y = rotation[1].to_radians();
-                    let radians_z = rotation[2].to_radians();
-                    let quat = nalgebra_glm::quat_angle_axis(radians_z, &nalgebra_glm::Vec3::new(0.0, 0.0, 1.0))
-                        * nalgebra_glm::quat_angle_axis(radians_y, &nalgebra_glm::Vec3::new(0.0, 1.0, 0.0))
-                        * nalgebra_glm::quat_angle_axis(radians_x, &nalgebra_glm::Vec3::new(1.0, 0.0, 0.0));
+                    let quat = euler_degrees_to_quat(rotation);
                     if let Some(transform) = world.get_local_transform_mut(entity) {
                         transform.rotation = quat;
                     }
                     world.set_local_transform_dirty(entity, LocalTransformDirty);
+                    self.log_change(format!("Turned '{name}' to face [{}, {}, {}] degrees", rotation[0], rotation[1], rotation[2]));
                     self.respond_success(&format!("Rotated entity '{name}' to [{}, {}, {}] degrees", rotation[0], rotation[1], rotation[2]));
                 } else {
-                    self.respond_success(&format!("Error: entity '{name}' not found"));
+                    self.respond_error(&format!("entity '{name}' not found"));
                 }
             }
             McpCommand::ScaleEntity { name, scale } => {
+                if self.refuse_if_locked(&name) {
+                    return;
+                }
                 if let Some(&entity) = self.scene.entities.get(&name) {
                     if let Some(transform) = world.get_local_transform_mut(entity) {
                         transform.scale = nalgebra_glm::Vec3::new(scale[0], scale[1], scale[2]);
                     }
                     world.set_local_transform_dirty(entity, LocalTransformDirty);
+                    self.log_change(format!("Resized '{name}' to [{}, {}, {}]", scale[0], scale[1], scale[2]));
                     self.respond_success(&format!("Scaled entity '{name}' to [{}, {}, {}]", scale[0], scale[1], scale[2]));
                 } else {
-                    self.respond_success(&format!("Error: entity '{name}' not found"));
+                    self.respond_error(&format!("entity '{name}' not found"));
                 }
             }
+            McpCommand::MoveEntities { names, delta } => {
+                let offset = nalgebra_glm::Vec3::new(delta[0], delta[1], delta[2]);
+                self.transform_group(world, &names, "Moved", |transform, _center| {
+                    transform.translation += offset;
+                });
+            }
+            McpCommand::RotateEntities { names, degrees } => {
+                let quat = euler_degrees_to_quat(degrees);
+                self.transform_group(world, &names, "Rotated", |transform, center| {
+                    transform.translation = center + nalgebra_glm::quat_rotate_vec3(&quat, &(transform.translation - center));
+                    transform.rotation = quat * transform.rotation;
+                });
+            }
+            McpCommand::ScaleEntities { names, factor } => {
+                if !(factor > 0.0) {
+                    self.respond_error("factor must be positive");
+                    return;
+                }
+                self.transform_group(world, &names, "Scaled", |transform, center| {
+                    transform.translation = center + (transform.translation - center) * factor;
+                    transform.scale *= factor;
+                });
+            }
+            McpCommand::SetLocked { name, locked } => {
+                if !self.scene.entities.contains_key(&name) {
+                    self.respond_error(&format!("entity '{name}' not found"));
+                    return;
+                }
+                if locked {
+                    self.scene.locked.insert(name.clone());
+                    self.log_change(format!("Locked '{name}' in place"));
+                    self.respond_success(&format!("Locked entity '{name}'"));
+                } else {
+                    self.scene.locked.remove(&name);
+                    self.log_change(format!("Unlocked '{name}'"));
+                    self.respond_success(&format!("Unlocked entity '{name}'"));
+                }
+            }
+            McpCommand::ApplyMaterial { names, prefix, preset, material } => {
+                let changes = match preset.as_deref() {
+                    Some(preset_name) => match find_material_preset(preset_name) {
+                        Some(preset) => preset.overrides().merged_with(&material),
+                        None => {
+                            let presets: Vec<&str> = MATERIAL_PRESETS.iter().map(|preset| preset.name).collect();
+                            self.respond_error(&format!("unknown preset '{preset_name}'. Use: {}", presets.join(", ")));
+                            return;
+                        }
+                    },
+                    None => material,
+                };
+                if changes.is_empty() {
+                    self.respond_error("provide a preset or at least one material value");
+                    return;
+                }
+
+                let targets = self.scene.select_entities(&names, prefix.as_deref());
+                let missing: Vec<&str> = names.iter()
+                    .filter(|name| !self.scene.entities.contains_key(*name))
+                    .map(String::as_str)
+                    .collect();
+                if targets.is_empty() {
+                    self.respond_error("no entities matched the names or prefix");
+                    return;
+                }
+
+                for name in &targets {
+                    let overrides = self.scene.materials.get(name).cloned().unwrap_or_default().merged_with(&changes);
+                    self.apply_material(world, name, &overrides);
+                }
+                let description = preset.map(|preset| format!("{preset} material")).unwrap_or_else(|| "new material".to_string());
+                self.log_change(format!("Gave {} entities a {description}", targets.len()));
+                let mut message = format!("Changed material of {} entities", targets.len());
+                if !missing.is_empty() {
+                    message.push_str(&format!("; not found: {}", missing.join(", ")));
+                }
+                self.respond_success(&message);
+            }
+            McpCommand::Undo | McpCommand::Redo => {
+                match self.step_history(world, matches!(command, McpCommand::Redo)) {
+                    Ok(message) => self.respond_success(&message),
+                    Err(message) => self.respond_error(&message),
+                }
+            }
+            McpCommand::GetHistory => {
+                let json = self.scene.history.to_json();
+                self.respond_success(&serde_json::to_string_pretty(&json).unwrap_or_default());
+            }
             McpCommand::SetCamera { focus, radius, yaw, pitch } => {
                 if let Some(camera_entity) = self.scene.camera_entity {
                     let yaw_rad = yaw.to_radians();
@@ -563,34 +1757,297 @@ impl Watchtower {
                     }
                     self.respond_success(&format!("Camera set: focus=[{}, {}, {}], radius={radius}, yaw={yaw}, pitch={pitch}", focus[0], focus[1], focus[2]));
                 } else {
-                    self.respond_success("Error: no camera (3D window not open)");
+                    self.respond_error("no camera (3D window not open)");
                 }
             }
-            McpCommand::ListEntities => {
-                let mut entries = Vec::new();
+            McpCommand::CameraFollow { target_name, offset, smoothing } => {
+                let Some(target) = target_name else {
+                    let message = match self.scene.camera_follow.take() {
+                        Some(follow) => format!("Camera stopped following '{}'", follow.target),
+                        None => "Camera was not following an entity".to_string(),
+                    };
+                    self.respond_success(&message);
+                    return;
+                };
+                if self.scene.camera_entity.is_none() {
+                    self.respond_error("no camera (3D window not open)");
+                    return;
+                }
+                if !self.scene.entities.contains_key(&target) {
+                    self.respond_error(&format!("entity '{target}' not found"));
+                    return;
+                }
+                if !(smoothing > 0.0) {
+                    self.respond_error("smoothing must be positive");
+                    return;
+                }
+                self.scene.camera_follow = Some(CameraFollow {
+                    target: target.clone(),
+                    offset: nalgebra_glm::Vec3::new(offset[0], offset[1], offset[2]),
+                    smoothing,
+                });
+                self.log_change(format!("Camera now follows '{target}'"));
+                self.respond_success(&format!("Camera following '{target}'"));
+            }
+            McpCommand::SetTransformMatrix { name, matrix } => {
+                if self.refuse_if_locked(&name) {
+                    return;
+                }
+                let Some(&entity) = self.scene.entities.get(&name) else {
+                    self.respond_error(&format!("entity '{name}' not found"));
+                    return;
+                };
+                let (translation, rotation, scale) = match decompose_transform_matrix(&matrix) {
+                    Ok(parts) => parts,
+                    Err(message) => {
+                        self.respond_error(&message);
+                        return;
+                    }
+                };
+                if let Some(transform) = world.get_local_transform_mut(entity) {
+                    transform.translation = translation;
+                    transform.rotation = rotation;
+                    transform.scale = scale;
+                }
+                world.set_local_transform_dirty(entity, LocalTransformDirty);
+                self.log_change(format!("Placed '{name}' at [{}, {}, {}]", translation.x, translation.y, translation.z));
+                self.respond_success(&format!(
+                    "Set transform of entity '{name}': translation=[{}, {}, {}], scale=[{}, {}, {}]",
+                    translation.x, translation.y, translation.z, scale.x, scale.y, scale.z
+                ));
+            }
+            McpCommand::GetTransformMatrix { name } => {
+                let matrix = self.scene.entities.get(&name)
+                    .and_then(|&entity| world.get_local_transform(entity))
+                    .map(compose_transform_matrix);
+                match matrix {
+                    Some(matrix) => {
+                        let json = serde_json::json!({
+                            "name": name,
+                            "layout": "column-major",
+                            "matrix": matrix,
+                        });
+                        self.respond_success(&serde_json::to_string_pretty(&json).unwrap_or_default());
+                    }
+                    None => self.respond_error(&format!("entity '{name}' not found")),
+                }
+            }
+            McpCommand::PickEntity { x, y } => {
+                if !self.scene.is_open() {
+                    self.respond_error("3D window is not open");
+                    return;
+                }
+                let name = self.scene.pick_entity(world, x, y);
+                let json = serde_json::json!({ "name": name });
+                self.respond_success(&json.to_string());
+            }
+            McpCommand::SetShadows { enabled, bias } => {
+                self.scene.shadows_enabled = Some(enabled);
+                if bias.is_some() {
+                    self.scene.shadow_bias = bias;
+                }
+                self.scene.apply_shadow_settings(world);
+
+                let current = self.scene.sun_entity
+                    .and_then(|sun| world.get_light(sun))
+                    .map(|light| (light.cast_shadows, light.shadow_bias));
+                match current {
+                    Some((cast_shadows, shadow_bias)) => self.respond_success(&format!(
+                        "Sun shadows: enabled={cast_shadows}, bias={shadow_bias}"
+                    )),
+                    None => self.respond_success(&format!(
+                        "Shadow settings saved (enabled={enabled}); they will apply when the 3D window opens"
+                    )),
+                }
+            }
+            McpCommand::SetGridFloor { enabled, size, spacing } => {
+                if !self.scene.is_open() {
+                    self.respond_error("3D window is not open");
+                    return;
+                }
+                if !enabled {
+                    self.scene.clear_grid_floor(world);
+                    self.respond_success("Grid floor hidden");
+                    return;
+                }
+                if !(size > 0.0) || !(spacing > 0.0) {
+                    self.respond_error("size and spacing must be positive");
+                    return;
+                }
+                let line_count = self.scene.spawn_grid_floor(world, size, spacing);
+                self.respond_success(&format!(
+                    "Grid floor shown: {line_count} lines, size {size}, spacing {spacing}"
+                ));
+            }
+            McpCommand::GetFrameStats { count } => {
+                let samples = self.frame_stats.recent(count.unwrap_or(MAX_FRAME_STAT_SAMPLES));
+                let json = serde_json::json!({
+                    "sample_seconds": 1,
+                    "samples": samples,
+                });
+                self.respond_success(&serde_json::to_string_pretty(&json).unwrap_or_default());
+            }
+            McpCommand::ReloadSite => {
+                if !self.dev_mode {
+                    self.respond_error("reload_site is only available when Watchtower is started with WATCHTOWER_DEV=1");
+                    return;
+                }
+                self.ctx.send(BackendEvent::ReloadSite);
+                self.respond_success(&format!("Reloading UI from {}", site_dist_dir().display()));
+            }
+            McpCommand::AnalyzePerformance => {
+                let samples = self.frame_stats.recent(MAX_FRAME_STAT_SAMPLES);
+                let metrics = SceneMetrics {
+                    entity_count: self.scene.entities.len(),
+                    animated_count: self.scene.highlights.len()
+                        + self.scene.emissive_pulses.len()
+                        + self.scene.orbits.len()
+                        + self.scene.velocities.len()
+                        + self.scene.billboards.len()
+                        + self.scene.trails.len(),
+                    grid_line_count: self.scene.grid_entities.len(),
+                    shadows_enabled: self.scene.shadows_enabled.unwrap_or(true),
+                };
+                let mut recommendations = performance_recommendations(&samples, &metrics);
+                if recommendations.is_empty() {
+                    recommendations.push("No performance problems detected.".to_string());
+                }
+                let latest = samples.last();
+                let json = serde_json::json!({
+                    "entity_count": metrics.entity_count,
+                    "animated_count": metrics.animated_count,
+                    "grid_line_count": metrics.grid_line_count,
+                    "shadows_enabled": metrics.shadows_enabled,
+                    "latest_frame_ms": latest.map(|sample| sample.average_frame_ms),
+                    "sampled_seconds": samples.len(),
+                    "recommendations": recommendations,
+                });
+                self.respond_success(&serde_json::to_string_pretty(&json).unwrap_or_default());
+            }
+            McpCommand::SetSpawnAnimation { enabled, per_entity_delay_ms } => {
+                if enabled {
+                    self.scene.spawn_reveal_delay = Some(per_entity_delay_ms as f32 / 1000.0);
+                    self.respond_success(&format!(
+                        "Spawn animation enabled: entities scale in {per_entity_delay_ms} ms apart"
+                    ));
+                } else {
+                    self.scene.spawn_reveal_delay = None;
+                    self.scene.finish_spawn_reveals(world);
+                    self.respond_success("Spawn animation disabled; entities appear instantly");
+                }
+            }
+            McpCommand::GetCapabilities => {
+                let prefabs: Vec<&str> = PREFABS.iter().map(|prefab| prefab.name).collect();
+                let presets: Vec<&str> = MATERIAL_PRESETS.iter().map(|preset| preset.name).collect();
+                let json = serde_json::json!({
+                    "shapes": SHAPES,
+                    "prefabs": prefabs,
+                    "material_presets": presets,
+                });
+                self.respond_success(&serde_json::to_string_pretty(&json).unwrap_or_default());
+            }
+            McpCommand::GetVersion => {
+                let json = serde_json::json!({
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "protocol_version": PROTOCOL_VERSION,
+                    "mcp_port": MCP_PORT,
+                    "capabilities": self.capabilities(),
+                });
+                self.respond_success(&serde_json::to_string_pretty(&json).unwrap_or_default());
+            }
+            McpCommand::GetSceneBounds => {
+                let json = match self.scene.bounds(world) {
+                    Some((min, max)) => {
+                        let center = (min + max) * 0.5;
+                        let size = max - min;
+                        serde_json::json!({
+                            "min": [min.x, min.y, min.z],
+                            "max": [max.x, max.y, max.z],
+                            "center": [center.x, center.y, center.z],
+                            "size": [size.x, size.y, size.z],
+                        })
+                    }
+                    None => serde_json::Value::Null,
+                };
+                self.respond_success(&serde_json::to_string_pretty(&json).unwrap_or_default());
+            }
+            McpCommand::GetLighting => {
+                let sun = self.scene.sun_entity.and_then(|sun| {
+                    let light = world.get_light(sun)?;
+                    let rotation = world.get_local_transform(sun)
+                        .map(|transform| transform.rotation)
+                        .unwrap_or_else(nalgebra_glm::quat_identity);
+                    let direction = nalgebra_glm::quat_rotate_vec3(&rotation, &nalgebra_glm::vec3(0.0, 0.0, -1.0));
+                    Some(serde_json::json!({
+                        "direction": [direction.x, direction.y, direction.z],
+                        "intensity": light.intensity,
+                        "color": [light.color.x, light.color.y, light.color.z],
+                        "cast_shadows": light.cast_shadows,
+                        "shadow_bias": light.shadow_bias,
+                    }))
+                });
+                let mut entity_lights = Vec::new();
                 for (name, &entity) in &self.scene.entities {
+                    let Some(light) = world.get_light(entity) else {
+                        continue;
+                    };
+                    let position = world.get_local_transform(entity)
+                        .map(|transform| [transform.translation.x, transform.translation.y, transform.translation.z])
+                        .unwrap_or([0.0, 0.0, 0.0]);
+                    entity_lights.push(serde_json::json!({
+                        "name": name,
+                        "position": position,
+                        "intensity": light.intensity,
+                        "color": [light.color.x, light.color.y, light.color.z],
+                        "cast_shadows": light.cast_shadows,
+                    }));
+                }
+                let json = serde_json::json!({
+                    "sun": sun,
+                    "shadows_enabled": self.scene.shadows_enabled.unwrap_or(true),
+                    "point_lights": entity_lights,
+                });
+                self.respond_success(&serde_json::to_string_pretty(&json).unwrap_or_default());
+            }
+            McpCommand::ListEntities { offset, limit } => {
+                let total = self.scene.entities.len();
+                let limit = limit.unwrap_or(MAX_LIST_ENTITIES).min(MAX_LIST_ENTITIES);
+                let mut entries = Vec::new();
+                for (name, &entity) in self.scene.entities.iter().skip(offset).take(limit) {
                     let position = world.get_local_transform(entity)
                         .map(|transform| [transform.translation.x, transform.translation.y, transform.translation.z])
                         .unwrap_or([0.0, 0.0, 0.0]);
                     entries.push(serde_json::json!({
                         "name": name,
                         "position": position,
+                        "locked": self.scene.locked.contains(name),
+                        "velocity": self.scene.velocities.get(name)
+                            .map(|velocity| [velocity.linear.x, velocity.linear.y, velocity.linear.z]),
                     }));
                 }
-                let json = serde_json::to_string_pretty(&entries).unwrap_or_default();
+
+                let end = offset.saturating_add(entries.len());
+                let next_offset = if end < total { Some(end) } else { None };
+                let json = serde_json::to_string_pretty(&serde_json::json!({
+                    "total": total,
+                    "offset": offset,
+                    "next_offset": next_offset,
+                    "entities": entries,
+                })).unwrap_or_default();
                 self.respond_success(&json);
             }
+            McpCommand::ReportToolError { tool_name, message } => {
+                self.ctx.send(BackendEvent::ToolError { tool_name, message });
+            }
             McpCommand::ClearScene => {
-                let count = self.scene.entities.len();
-                for (_name, entity) in self.scene.entities.drain() {
-                    despawn_recursive_immediate(world, entity);
-                }
+                let count = self.scene.clear_entities(world);
+                self.log_change(format!("Cleared the scene, removing {count} entities"));
                 self.respond_success(&format!("Cleared {count} entities from scene"));
             }
         }
     }
 
-    fn handle_run_test(&mut self, test_name: &str) {
+    fn handle_run_test(&mut self, world: &mut World, test_name: &str) {
         match test_name {
             "ipc_echo" => {
                 let start = Instant::now();
@@ -621,7 +2078,7 @@ impl Watchtower {
                         }
                     });
 
-                    let result = ureq::post("http://127.0.0.1:3334/mcp")
+                    let result = ureq::post(&format!("http://127.0.0.1:{MCP_PORT}/mcp"))
                         .set("Content-Type", "application/json")
                         .set("Accept", "application/json, text/event-stream")
                         .send_string(&mcp_init_body.to_string());
@@ -630,7 +2087,7 @@ impl Watchtower {
                     match result {
                         Ok(response) => {
                             let status = response.status();
-                            let _ = sender.send(BackendEvent::TestResult {
+                            sender.send(BackendEvent::TestResult {
                                 test_name: "mcp_round_trip".to_string(),
                                 success: (200..300).contains(&status),
                                 message: format!("MCP server responded with status {status}"),
@@ -638,7 +2095,7 @@ impl Watchtower {
                             });
                         }
                         Err(error) => {
-                            let _ = sender.send(BackendEvent::TestResult {
+                            sender.send(BackendEvent::TestResult {
                                 test_name: "mcp_round_trip".to_string(),
                                 success: false,
                                 message: format!("MCP request failed: {error}"),
@@ -675,6 +2132,17 @@ impl Watchtower {
                 });
             }
 
+            "history_pipeline" => {
+                let start = Instant::now();
+                let result = verify_history_pipeline().and_then(|()| verify_history_restore(world));
+                self.ctx.send(BackendEvent::TestResult {
+                    test_name: "history_pipeline".to_string(),
+                    success: result.is_ok(),
+                    message: result.err().unwrap_or_else(|| "Undo, redo, branching, get_history JSON, and scene restoration behave as expected".to_string()),
+                    duration_ms: start.elapsed().as_millis() as u64,
+                });
+            }
+
             "status_cycle" => {
                 let sender = self.test_result_tx.clone();
                 std::thread::spawn(move || {
@@ -687,13 +2155,13 @@ impl Watchtower {
                         AgentStatus::Idle,
                     ];
                     for status in statuses {
-                        let _ = sender.send(BackendEvent::StatusUpdate {
+                        sender.send(BackendEvent::StatusUpdate {
                             status,
                         });
                         std::thread::sleep(std::time::Duration::from_millis(500));
                     }
                     let elapsed = start.elapsed();
-                    let _ = sender.send(BackendEvent::TestResult {
+                    sender.send(BackendEvent::TestResult {
                         test_name: "status_cycle".to_string(),
                         success: true,
                         message: "Cycled through all status values".to_string(),
@@ -711,18 +2179,20 @@ impl Watchtower {
                     prompt: "Say hello in exactly 3 words".to_string(),
                     session_id: None,
                     model: None,
+                    image: None,
                 });
 
                 let flag = self.cli_prompt_test_running.clone();
                 let sender = self.test_result_tx.clone();
+                let timeout_secs = with_env_overrides(&self.config).cli_test_timeout_secs;
                 std::thread::spawn(move || {
-                    std::thread::sleep(std::time::Duration::from_secs(60));
+                    std::thread::sleep(std::time::Duration::from_secs(timeout_secs));
                     if flag.swap(false, Ordering::SeqCst) {
-                        let _ = sender.send(BackendEvent::TestResult {
+                        sender.send(BackendEvent::TestResult {
                             test_name: "cli_prompt".to_string(),
                             success: false,
-                            message: "Timed out after 60s waiting for CLI response".to_string(),
-                            duration_ms: 60_000,
+                            message: format!("Timed out after {timeout_secs}s waiting for CLI response"),
+                            duration_ms: timeout_secs * 1000,
                         });
                     }
                 });
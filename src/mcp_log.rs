@@ -0,0 +1,103 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::time::Instant;
+
+use crate::mcp_server::McpCommand;
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct McpLogEntry {
+    elapsed_ms: u64,
+    command: McpCommand,
+}
+
+pub struct McpLogger {
+    file: Option<File>,
+    started: Instant,
+}
+
+impl McpLogger {
+    pub fn from_env() -> Self {
+        let file = std::env::var_os("WATCHTOWER_MCP_LOG").and_then(|path| {
+            File::options()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .map_err(|error| eprintln!("Failed to open MCP log {}: {error}", path.to_string_lossy()))
+                .ok()
+        });
+        Self {
+            file,
+            started: Instant::now(),
+        }
+    }
+
+    pub fn log(&mut self, command: &McpCommand) {
+        let Some(file) = self.file.as_mut() else {
+            return;
+        };
+        let entry = McpLogEntry {
+            elapsed_ms: self.started.elapsed().as_millis() as u64,
+            command: command.clone(),
+        };
+        if let Ok(line) = serde_json::to_string(&entry) {
+            let _ = writeln!(file, "{line}");
+        }
+    }
+}
+
+pub struct McpReplay {
+    entries: VecDeque<McpLogEntry>,
+    total: usize,
+    started: Instant,
+}
+
+impl McpReplay {
+    pub fn from_env() -> Option<Self> {
+        let path = std::env::var_os("WATCHTOWER_MCP_REPLAY")?;
+        let file = match File::open(&path) {
+            Ok(file) => file,
+            Err(error) => {
+                eprintln!("Failed to open MCP replay {}: {error}", path.to_string_lossy());
+                return None;
+            }
+        };
+
+        let mut entries = VecDeque::new();
+        for (line_number, line) in BufReader::new(file).lines().map_while(Result::ok).enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<McpLogEntry>(&line) {
+                Ok(entry) => entries.push_back(entry),
+                Err(error) => eprintln!("Skipping MCP replay line {}: {error}", line_number + 1),
+            }
+        }
+        eprintln!("Replaying {} MCP commands from {}", entries.len(), path.to_string_lossy());
+
+        Some(Self {
+            total: entries.len(),
+            entries,
+            started: Instant::now(),
+        })
+    }
+
+    pub fn due_commands(&mut self) -> Vec<McpCommand> {
+        let elapsed_ms = self.started.elapsed().as_millis() as u64;
+        let mut due = Vec::new();
+        while self.entries.front().is_some_and(|entry| entry.elapsed_ms <= elapsed_ms) {
+            if let Some(entry) = self.entries.pop_front() {
+                due.push(entry.command);
+            }
+        }
+        due
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn total(&self) -> usize {
+        self.total
+    }
+}
@@ -1,15 +1,31 @@
 use rmcp::{
-    ServerHandler,
-    handler::server::{router::tool::ToolRouter, tool::Parameters},
+    Error as McpError, RoleServer, ServerHandler,
+    handler::server::{
+        router::tool::ToolRouter,
+        tool::{Parameters, ToolCallContext},
+    },
     model::*,
-    tool, tool_handler, tool_router,
+    service::RequestContext,
+    tool, tool_router,
     transport::streamable_http_server::{
         StreamableHttpService, session::local::LocalSessionManager,
     },
 };
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 
-#[derive(Clone)]
+use crate::mcp_validation::validate_arguments;
+
+const MAX_WAIT_SECONDS: f32 = 10.0;
+const RESPONSE_POLL_INTERVAL: Duration = Duration::from_millis(50);
+const DEFAULT_RESPONSE_TIMEOUT: Duration = Duration::from_secs(5);
+const ASSET_RESPONSE_TIMEOUT: Duration = Duration::from_secs(30);
+const USER_INPUT_RESPONSE_TIMEOUT: Duration = Duration::from_secs(600);
+pub const MAX_LIST_ENTITIES: usize = 200;
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub enum McpCommand {
     ShowNotification { title: String, body: String },
     DisplayContent { content: String, format: String },
@@ -17,29 +33,194 @@ pub enum McpCommand {
     SetStatusMessage { message: String },
     Open3dWindow { width: u32, height: u32 },
     Close3dWindow,
-    SpawnEntity { name: String, shape: String, position: [f32; 3], scale: [f32; 3] },
+    SpawnEntity { name: String, shape: String, position: [f32; 3], scale: [f32; 3], material: MaterialOverrides },
     RemoveEntity { name: String },
     MoveEntity { name: String, position: [f32; 3] },
-    RotateEntity { name: String, rotation: [f32; 3] },
+    RotateEntity { name: String, rotation: [f32; 3], snap_degrees: Option<f32> },
     ScaleEntity { name: String, scale: [f32; 3] },
+    MoveEntities { names: Vec<String>, delta: [f32; 3] },
+    RotateEntities { names: Vec<String>, degrees: [f32; 3] },
+    ScaleEntities { names: Vec<String>, factor: f32 },
+    SetLocked { name: String, locked: bool },
+    ApplyMaterial { names: Vec<String>, prefix: Option<String>, preset: Option<String>, material: MaterialOverrides },
+    Undo,
+    Redo,
+    GetHistory,
     SetCamera { focus: [f32; 3], radius: f32, yaw: f32, pitch: f32 },
-    ListEntities,
+    CameraFollow { target_name: Option<String>, offset: [f32; 3], smoothing: f32 },
+    SetTransformMatrix { name: String, matrix: [f32; 16] },
+    GetTransformMatrix { name: String },
+    PickEntity { x: f32, y: f32 },
+    SetShadows { enabled: bool, bias: Option<f32> },
+    ImportGltf { name: String, path: String, position: [f32; 3], scale: [f32; 3] },
+    SpawnPrefab { prefab: String, prefix: String, position: [f32; 3], scale: f32 },
+    HighlightEntity { name: String, duration_secs: f32 },
+    SetGridFloor { enabled: bool, size: f32, spacing: f32 },
+    GetFrameStats { count: Option<usize> },
+    AnalyzePerformance,
+    SetOpacity { name: String, alpha: f32 },
+    SetSpawnAnimation { enabled: bool, per_entity_delay_ms: u64 },
+    SetTexture { name: String, texture_path: String },
+    SetEmissivePulse { name: String, color: [f32; 3], speed: f32, enabled: bool },
+    SetOrbit { name: String, center: [f32; 3], radius: f32, speed_deg_per_sec: f32, axis: [f32; 3] },
+    SetVelocity { name: String, velocity: [f32; 3], gravity: bool, bounciness: f32 },
+    SetBillboard { name: String, enabled: bool },
+    SetTrail { name: String, length: usize, color: [f32; 3], enabled: bool },
+    AddKeyframe { name: String, time: f32, position: Option<[f32; 3]>, rotation: Option<[f32; 3]>, scale: Option<[f32; 3]> },
+    Seek { time: f32 },
+    ListAnimations,
+    ClearAnimations { name: Option<String> },
+    ReloadSite,
+    GetConfig,
+    SetConfig {
+        default_model: Option<String>,
+        cli_test_timeout_secs: Option<u64>,
+        shadows_enabled: Option<bool>,
+        keyboard_camera: Option<bool>,
+        preserve_camera_on_assemble: Option<bool>,
+    },
+    GetCapabilities,
+    GetVersion,
+    GetSceneBounds,
+    GetLighting,
+    ListEntities { offset: usize, limit: Option<usize> },
     ClearScene,
+    ReportToolError { tool_name: String, message: String },
+}
+
+impl McpCommand {
+    pub fn response_timeout(&self) -> Duration {
+        match self {
+            McpCommand::RequestUserInput { .. } => USER_INPUT_RESPONSE_TIMEOUT,
+            McpCommand::ImportGltf { .. } | McpCommand::SetTexture { .. } => ASSET_RESPONSE_TIMEOUT,
+            _ => DEFAULT_RESPONSE_TIMEOUT,
+        }
+    }
+
+    pub fn is_untracked_scene_change(&self) -> bool {
+        matches!(
+            self,
+            McpCommand::SetLocked { .. }
+                | McpCommand::SetCamera { .. }
+                | McpCommand::CameraFollow { .. }
+                | McpCommand::SetShadows { .. }
+                | McpCommand::HighlightEntity { .. }
+                | McpCommand::SetGridFloor { .. }
+                | McpCommand::SetSpawnAnimation { .. }
+        )
+    }
+
+    pub fn tool_name(&self) -> &'static str {
+        match self {
+            McpCommand::ShowNotification { .. } => "show_notification",
+            McpCommand::DisplayContent { .. } => "display_content",
+            McpCommand::RequestUserInput { .. } => "request_user_input",
+            McpCommand::SetStatusMessage { .. } => "set_status_message",
+            McpCommand::Open3dWindow { .. } => "open_3d_window",
+            McpCommand::Close3dWindow => "close_3d_window",
+            McpCommand::SpawnEntity { .. } => "spawn_entity",
+            McpCommand::RemoveEntity { .. } => "remove_entity",
+            McpCommand::MoveEntity { .. } => "move_entity",
+            McpCommand::RotateEntity { .. } => "rotate_entity",
+            McpCommand::ScaleEntity { .. } => "scale_entity",
+            McpCommand::MoveEntities { .. } => "move_entities",
+            McpCommand::RotateEntities { .. } => "rotate_entities",
+            McpCommand::ScaleEntities { .. } => "scale_entities",
+            McpCommand::SetLocked { .. } => "lock_entity",
+            McpCommand::ApplyMaterial { .. } => "apply_material",
+            McpCommand::Undo => "undo",
+            McpCommand::Redo => "redo",
+            McpCommand::GetHistory => "get_history",
+            McpCommand::SetCamera { .. } => "set_camera",
+            McpCommand::CameraFollow { .. } => "set_camera_follow",
+            McpCommand::SetTransformMatrix { .. } => "set_transform_matrix",
+            McpCommand::GetTransformMatrix { .. } => "get_transform_matrix",
+            McpCommand::PickEntity { .. } => "get_entity_at",
+            McpCommand::SetShadows { .. } => "set_shadows",
+            McpCommand::ImportGltf { .. } => "import_gltf_as_entity",
+            McpCommand::SpawnPrefab { .. } => "spawn_prefab",
+            McpCommand::HighlightEntity { .. } => "highlight_entity",
+            McpCommand::SetGridFloor { .. } => "set_grid_floor",
+            McpCommand::GetFrameStats { .. } => "get_frame_stats",
+            McpCommand::AnalyzePerformance => "analyze_performance",
+            McpCommand::SetOpacity { .. } => "set_opacity",
+            McpCommand::SetSpawnAnimation { .. } => "set_spawn_animation",
+            McpCommand::SetTexture { .. } => "set_texture",
+            McpCommand::SetEmissivePulse { .. } => "set_entity_emissive_pulse",
+            McpCommand::SetOrbit { .. } => "set_entity_orbit",
+            McpCommand::SetVelocity { .. } => "set_velocity",
+            McpCommand::SetBillboard { .. } => "set_billboard",
+            McpCommand::SetTrail { .. } => "set_entity_trail",
+            McpCommand::AddKeyframe { .. } => "add_keyframe",
+            McpCommand::Seek { .. } => "seek",
+            McpCommand::ListAnimations => "list_animations",
+            McpCommand::ClearAnimations { .. } => "clear_animations",
+            McpCommand::ReloadSite => "reload_site",
+            McpCommand::GetConfig => "get_config",
+            McpCommand::SetConfig { .. } => "set_config",
+            McpCommand::GetCapabilities => "get_capabilities",
+            McpCommand::GetVersion => "get_version",
+            McpCommand::GetSceneBounds => "get_scene_bounds",
+            McpCommand::GetLighting => "get_lighting",
+            McpCommand::ListEntities { .. } => "list_entities",
+            McpCommand::ClearScene => "clear_scene",
+            McpCommand::ReportToolError { .. } => "report_tool_error",
+        }
+    }
+}
+
+#[derive(Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct MaterialOverrides {
+    pub color: Option<[f32; 4]>,
+    pub roughness: Option<f32>,
+    pub metallic: Option<f32>,
+    pub emissive: Option<[f32; 3]>,
+    pub texture: Option<String>,
+}
+
+impl MaterialOverrides {
+    pub fn is_empty(&self) -> bool {
+        self.color.is_none()
+            && self.roughness.is_none()
+            && self.metallic.is_none()
+            && self.emissive.is_none()
+            && self.texture.is_none()
+    }
+
+    pub fn merged_with(&self, changes: &MaterialOverrides) -> MaterialOverrides {
+        MaterialOverrides {
+            color: changes.color.or(self.color),
+            roughness: changes.roughness.or(self.roughness),
+            metallic: changes.metallic.or(self.metallic),
+            emissive: changes.emissive.or(self.emissive),
+            texture: changes.texture.clone().or_else(|| self.texture.clone()),
+        }
+    }
 }
 
 #[derive(Clone)]
 pub enum McpResponse {
     Success(String),
+    Error(String),
     UserInput(String),
 }
 
-pub type WatchtowerCommandQueue = Arc<RwLock<Vec<McpCommand>>>;
-pub type WatchtowerResponseQueue = Arc<RwLock<Option<McpResponse>>>;
+pub const MCP_PORT: u16 = 3334;
+
+static NEXT_COMMAND_ID: AtomicU64 = AtomicU64::new(1);
+
+pub struct QueuedCommand {
+    pub id: u64,
+    pub command: McpCommand,
+}
+
+pub type WatchtowerCommandQueue = Arc<RwLock<Vec<QueuedCommand>>>;
+pub type WatchtowerResponseQueue = Arc<RwLock<HashMap<u64, Option<McpResponse>>>>;
 
 pub fn create_watchtower_mcp_queues() -> (WatchtowerCommandQueue, WatchtowerResponseQueue) {
     (
         Arc::new(RwLock::new(Vec::new())),
-        Arc::new(RwLock::new(None)),
+        Arc::new(RwLock::new(HashMap::new())),
     )
 }
 
@@ -55,7 +236,7 @@ pub struct ShowNotificationRequest {
 pub struct DisplayContentRequest {
     #[schemars(description = "Content to display in the UI")]
     pub content: String,
-    #[schemars(description = "Format of the content: markdown, code, or text")]
+    #[schemars(description = "Format of the content: markdown, code, text, table, or diff. Tables accept CSV (first line is the header) or a JSON array of objects or arrays. Diffs render lines starting with + and - as added and removed")]
     pub format: String,
 }
 
@@ -91,6 +272,14 @@ pub struct SpawnEntityRequest {
     pub position: [f32; 3],
     #[schemars(description = "Scale as [x, y, z] (default: [1, 1, 1])")]
     pub scale: Option<[f32; 3]>,
+    #[schemars(description = "Base color as sRGB [r, g, b, a] in 0..1, like CSS colors divided by 255; alpha below 1 makes the entity transparent (default: engine default material)")]
+    pub color: Option<[f32; 4]>,
+    #[schemars(description = "Surface roughness in 0..1")]
+    pub roughness: Option<f32>,
+    #[schemars(description = "Metallic factor in 0..1")]
+    pub metallic: Option<f32>,
+    #[schemars(description = "Emissive color as linear [r, g, b]; values above 1 glow brighter")]
+    pub emissive: Option<[f32; 3]>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
@@ -113,6 +302,8 @@ pub struct RotateEntityRequest {
     pub name: String,
     #[schemars(description = "Rotation in degrees as [x, y, z] euler angles")]
     pub rotation: [f32; 3],
+    #[schemars(description = "Optional step in degrees, e.g. 90; each angle is rounded to the nearest multiple before applying. Omit to use the angles exactly.")]
+    pub snap_degrees: Option<f32>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
@@ -123,6 +314,56 @@ pub struct ScaleEntityRequest {
     pub scale: [f32; 3],
 }
 
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct LockEntityRequest {
+    #[schemars(description = "Name of the entity to lock or unlock")]
+    pub name: String,
+    #[schemars(description = "True to refuse transform changes to the entity, false to allow them again")]
+    pub locked: bool,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct ApplyMaterialRequest {
+    #[schemars(description = "Names of the entities to change (default: none)")]
+    pub names: Option<Vec<String>>,
+    #[schemars(description = "Also change every entity whose name starts with this prefix, e.g. 'brick_' or 'brick_*'")]
+    pub prefix: Option<String>,
+    #[schemars(description = "Material preset applied first: gold, chrome, copper, plastic, rubber, brick, glass, or neon")]
+    pub preset: Option<String>,
+    #[schemars(description = "Base color as sRGB [r, g, b, a] in 0..1, overriding the preset")]
+    pub color: Option<[f32; 4]>,
+    #[schemars(description = "Surface roughness in 0..1, overriding the preset")]
+    pub roughness: Option<f32>,
+    #[schemars(description = "Metallic factor in 0..1, overriding the preset")]
+    pub metallic: Option<f32>,
+    #[schemars(description = "Emissive color as linear [r, g, b], overriding the preset")]
+    pub emissive: Option<[f32; 3]>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct MoveEntitiesRequest {
+    #[schemars(description = "Names of the entities to move")]
+    pub names: Vec<String>,
+    #[schemars(description = "Offset to add to each position as [x, y, z]")]
+    pub delta: [f32; 3],
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct RotateEntitiesRequest {
+    #[schemars(description = "Names of the entities to rotate")]
+    pub names: Vec<String>,
+    #[schemars(description = "Relative rotation in degrees as [x, y, z] euler angles, applied around the group center")]
+    pub degrees: [f32; 3],
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct ScaleEntitiesRequest {
+    #[schemars(description = "Names of the entities to scale")]
+    pub names: Vec<String>,
+    #[schemars(description = "Uniform scale factor applied to each entity and its offset from the group center")]
+    pub factor: f32,
+}
+
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 pub struct SetCameraRequest {
     #[schemars(description = "Focus point as [x, y, z]")]
@@ -135,61 +376,334 @@ pub struct SetCameraRequest {
     pub pitch: f32,
 }
 
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SetCameraFollowRequest {
+    #[schemars(description = "Entity for the camera to follow; null stops following")]
+    pub target_name: Option<String>,
+    #[schemars(description = "Offset from the entity's position to the camera focus as [x, y, z] (default: [0, 0, 0])")]
+    pub offset: Option<[f32; 3]>,
+    #[schemars(description = "How quickly the focus catches up, per second; higher is snappier (default: 5)")]
+    pub smoothing: Option<f32>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SetTransformMatrixRequest {
+    #[schemars(description = "Name of the entity to transform")]
+    pub name: String,
+    #[schemars(description = "4x4 transform matrix as 16 floats in column-major order (translation in elements 12, 13, 14). Must be invertible and free of skew, with perpendicular basis axes")]
+    pub matrix: [f32; 16],
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct GetTransformMatrixRequest {
+    #[schemars(description = "Name of the entity to read")]
+    pub name: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct WaitRequest {
+    #[schemars(description = "Number of seconds to wait (capped at 10)")]
+    pub seconds: f32,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct ListEntitiesRequest {
+    #[schemars(description = "Number of entities to skip, in name order (default: 0)")]
+    pub offset: Option<usize>,
+    #[schemars(description = "Maximum number of entities to return (default and max: 200)")]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SetSpawnAnimationRequest {
+    #[schemars(description = "Whether new entities scale in one after another instead of appearing instantly")]
+    pub enabled: bool,
+    #[schemars(description = "Delay between consecutive entity reveals in milliseconds (default: 150)")]
+    pub per_entity_delay_ms: Option<u64>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SetOpacityRequest {
+    #[schemars(description = "Name of the entity")]
+    pub name: String,
+    #[schemars(description = "Opacity from 0 (invisible) to 1 (opaque); values below 1 enable alpha blending")]
+    pub alpha: f32,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SetTextureRequest {
+    #[schemars(description = "Name of the entity")]
+    pub name: String,
+    #[schemars(description = "Path to a PNG or JPEG image on disk (at most 4096x4096)")]
+    pub texture_path: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SetEmissivePulseRequest {
+    #[schemars(description = "Name of the entity to pulse")]
+    pub name: String,
+    #[schemars(description = "Peak emissive glow as linear [r, g, b], added on top of the entity's base emissive (default: [1.0, 0.8, 0.2])")]
+    pub color: Option<[f32; 3]>,
+    #[schemars(description = "Pulse frequency in cycles per second (default: 1.0)")]
+    pub speed: Option<f32>,
+    #[schemars(description = "Whether the pulse is active; false restores the base emissive (default: true)")]
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SetOrbitRequest {
+    #[schemars(description = "Name of the entity to orbit")]
+    pub name: String,
+    #[schemars(description = "Point to orbit around as [x, y, z] (default: [0, 0, 0])")]
+    pub center: Option<[f32; 3]>,
+    #[schemars(description = "Distance from the center")]
+    pub radius: f32,
+    #[schemars(description = "Angular speed in degrees per second; negative reverses direction and 0 stops the orbit")]
+    pub speed_deg_per_sec: f32,
+    #[schemars(description = "Axis to orbit around as [x, y, z] (default: [0, 1, 0])")]
+    pub axis: Option<[f32; 3]>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SetVelocityRequest {
+    #[schemars(description = "Name of the entity to move")]
+    pub name: String,
+    #[schemars(description = "Velocity in units per second as [x, y, z]; [0, 0, 0] without gravity stops the entity")]
+    pub velocity: [f32; 3],
+    #[schemars(description = "Whether gravity pulls the entity down and the ground at y = 0 stops it (default: false)")]
+    pub gravity: Option<bool>,
+    #[schemars(description = "Fraction of vertical speed kept when bouncing off the ground, 0..1 (default: 0.5)")]
+    pub bounciness: Option<f32>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SetTrailRequest {
+    #[schemars(description = "Name of the entity that leaves the trail")]
+    pub name: String,
+    #[schemars(description = "True to show a trail behind the entity as it moves, false to remove it")]
+    pub enabled: bool,
+    #[schemars(description = "Number of fading segments kept behind the entity (default: 20, max: 64)")]
+    pub length: Option<usize>,
+    #[schemars(description = "Trail color as sRGB [r, g, b] in 0..1; the segments also glow in this color (default: [1.0, 0.8, 0.2])")]
+    pub color: Option<[f32; 3]>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct AddKeyframeRequest {
+    #[schemars(description = "Name of the entity to animate")]
+    pub name: String,
+    #[schemars(description = "Timeline time in seconds; a keyframe already at this time is replaced")]
+    pub time: f32,
+    #[schemars(description = "Position as [x, y, z] at this time (default: the entity's current position)")]
+    pub position: Option<[f32; 3]>,
+    #[schemars(description = "Rotation in degrees as [x, y, z] euler angles at this time (default: the entity's current rotation)")]
+    pub rotation: Option<[f32; 3]>,
+    #[schemars(description = "Scale as [x, y, z] at this time (default: the entity's current scale)")]
+    pub scale: Option<[f32; 3]>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SeekRequest {
+    #[schemars(description = "Timeline time in seconds; times before the first or after the last keyframe hold that keyframe")]
+    pub time: f32,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SetBillboardRequest {
+    #[schemars(description = "Name of the entity")]
+    pub name: String,
+    #[schemars(description = "True to keep the entity facing the camera every frame, false to stop")]
+    pub enabled: bool,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct ClearAnimationsRequest {
+    #[schemars(description = "Entity whose animations to stop; omit to stop every animation in the scene")]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct GetFrameStatsRequest {
+    #[schemars(description = "Number of most recent one-second samples to return (default and max: 120)")]
+    pub count: Option<usize>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct PickEntityRequest {
+    #[schemars(description = "Horizontal screen coordinate in pixels, from the left edge of the 3D window")]
+    pub x: f32,
+    #[schemars(description = "Vertical screen coordinate in pixels, from the top edge of the 3D window")]
+    pub y: f32,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SetShadowsRequest {
+    #[schemars(description = "Whether the sun casts shadows")]
+    pub enabled: bool,
+    #[schemars(description = "Optional shadow bias to reduce shadow acne (leave unset to keep the current bias)")]
+    pub bias: Option<f32>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SetGridFloorRequest {
+    #[schemars(description = "Whether the reference grid is shown")]
+    pub enabled: bool,
+    #[schemars(description = "Width of the square grid in world units (default: 20)")]
+    pub size: Option<f32>,
+    #[schemars(description = "Distance between grid lines in world units (default: 1)")]
+    pub spacing: Option<f32>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct ImportGltfRequest {
+    #[schemars(description = "Unique name for the imported entity")]
+    pub name: String,
+    #[schemars(description = "Path to a .gltf or .glb file on disk")]
+    pub path: String,
+    #[schemars(description = "Position as [x, y, z] (default: [0, 0, 0])")]
+    pub position: Option<[f32; 3]>,
+    #[schemars(description = "Scale as [x, y, z] (default: [1, 1, 1])")]
+    pub scale: Option<[f32; 3]>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SpawnPrefabRequest {
+    #[schemars(description = "Prefab to spawn: tree, house, character, or fence")]
+    pub name: String,
+    #[schemars(description = "Prefix for the spawned entity names, e.g. 'tree_1' produces 'tree_1_trunk' and 'tree_1_foliage' (default: the prefab name)")]
+    pub prefix: Option<String>,
+    #[schemars(description = "Origin of the prefab as [x, y, z]; parts are placed relative to it with the base on y = 0")]
+    pub position: [f32; 3],
+    #[schemars(description = "Uniform scale applied to the whole prefab (default: 1)")]
+    pub scale: Option<f32>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct HighlightEntityRequest {
+    #[schemars(description = "Name of the entity to highlight")]
+    pub name: String,
+    #[schemars(description = "How long the glow pulse lasts in seconds (default: 2, max: 30)")]
+    pub duration_secs: Option<f32>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SetConfigRequest {
+    #[schemars(description = "Default Claude model for prompts that don't specify one (empty string clears it)")]
+    pub default_model: Option<String>,
+    #[schemars(description = "Seconds the cli_prompt self-test waits before timing out")]
+    pub cli_test_timeout_secs: Option<u64>,
+    #[schemars(description = "Whether the sun casts shadows by default")]
+    pub shadows_enabled: Option<bool>,
+    #[schemars(description = "Whether WASD/QE keys move the camera while the 3D window has focus")]
+    pub keyboard_camera: Option<bool>,
+    #[schemars(description = "Whether assembling a demo keeps the camera pose when the user has moved it since the last auto-framing")]
+    pub preserve_camera_on_assemble: Option<bool>,
+}
+
+struct PendingCommand {
+    id: u64,
+    command_queue: WatchtowerCommandQueue,
+    response_queue: WatchtowerResponseQueue,
+}
+
+impl PendingCommand {
+    fn queue(command_queue: &WatchtowerCommandQueue, response_queue: &WatchtowerResponseQueue, command: McpCommand) -> Self {
+        let id = NEXT_COMMAND_ID.fetch_add(1, Ordering::Relaxed);
+        response_queue.write().unwrap().insert(id, None);
+        command_queue.write().unwrap().push(QueuedCommand { id, command });
+        Self {
+            id,
+            command_queue: command_queue.clone(),
+            response_queue: response_queue.clone(),
+        }
+    }
+
+    fn take_response(&self) -> Option<McpResponse> {
+        self.response_queue.write().unwrap().get_mut(&self.id).and_then(Option::take)
+    }
+}
+
+impl Drop for PendingCommand {
+    fn drop(&mut self) {
+        self.command_queue.write().unwrap().retain(|queued| queued.id != self.id);
+        self.response_queue.write().unwrap().remove(&self.id);
+    }
+}
+
 #[derive(Clone)]
 pub struct WatchtowerMcpServer {
     tool_router: ToolRouter<Self>,
     command_queue: WatchtowerCommandQueue,
     response_queue: WatchtowerResponseQueue,
+    shutdown: Arc<AtomicBool>,
 }
 
 #[tool_router]
 impl WatchtowerMcpServer {
-    pub fn new(command_queue: WatchtowerCommandQueue, response_queue: WatchtowerResponseQueue) -> Self {
+    pub fn new(
+        command_queue: WatchtowerCommandQueue,
+        response_queue: WatchtowerResponseQueue,
+        shutdown: Arc<AtomicBool>,
+    ) -> Self {
         Self {
             tool_router: Self::tool_router(),
             command_queue,
             response_queue,
+            shutdown,
         }
     }
 
-    fn send_command_and_wait(&self, cmd: McpCommand) -> String {
-        {
-            let mut queue = self.command_queue.write().unwrap();
-            queue.push(cmd);
-        }
+    fn report_tool_error(&self, tool_name: &str, message: &str) {
+        let id = NEXT_COMMAND_ID.fetch_add(1, Ordering::Relaxed);
+        self.command_queue.write().unwrap().push(QueuedCommand {
+            id,
+            command: McpCommand::ReportToolError {
+                tool_name: tool_name.to_string(),
+                message: message.to_string(),
+            },
+        });
+    }
 
-        for _ in 0..100 {
-            std::thread::sleep(std::time::Duration::from_millis(50));
-            let mut response = self.response_queue.write().unwrap();
-            if let Some(resp) = response.take() {
+    async fn send_command_and_wait(&self, cmd: McpCommand) -> Result<String, String> {
+        let timeout = cmd.response_timeout();
+        let pending = PendingCommand::queue(&self.command_queue, &self.response_queue, cmd);
+
+        let started = Instant::now();
+        while started.elapsed() < timeout {
+            if self.shutdown.load(Ordering::SeqCst) {
+                return Err("Watchtower is shutting down".to_string());
+            }
+            tokio::time::sleep(RESPONSE_POLL_INTERVAL).await;
+            if let Some(resp) = pending.take_response() {
                 return match resp {
-                    McpResponse::Success(message) => message,
-                    McpResponse::UserInput(input) => input,
+                    McpResponse::Success(message) => Ok(message),
+                    McpResponse::Error(message) => Err(message),
+                    McpResponse::UserInput(input) => Ok(input),
                 };
             }
         }
 
-        "Timeout waiting for response".to_string()
+        Err(format!("Timeout waiting for response after {}s", timeout.as_secs()))
     }
 
     #[tool(description = "Show a notification in the Watchtower UI")]
-    async fn show_notification(&self, Parameters(request): Parameters<ShowNotificationRequest>) -> String {
+    async fn show_notification(&self, Parameters(request): Parameters<ShowNotificationRequest>) -> Result<String, String> {
         self.send_command_and_wait(McpCommand::ShowNotification {
             title: request.title,
             body: request.body,
-        })
+        }).await
     }
 
-    #[tool(description = "Display content (markdown, code, or text) in the Watchtower UI")]
-    async fn display_content(&self, Parameters(request): Parameters<DisplayContentRequest>) -> String {
+    #[tool(description = "Display content (markdown, code, text, table, or diff) in the Watchtower UI")]
+    async fn display_content(&self, Parameters(request): Parameters<DisplayContentRequest>) -> Result<String, String> {
         self.send_command_and_wait(McpCommand::DisplayContent {
             content: request.content,
             format: request.format,
-        })
+        }).await
     }
 
     #[tool(description = "Request input from the user via the Watchtower UI. Blocks until the user responds.")]
-    async fn request_user_input(&self, Parameters(request): Parameters<RequestUserInputRequest>) -> String {
+    async fn request_user_input(&self, Parameters(request): Parameters<RequestUserInputRequest>) -> Result<String, String> {
         let request_id = format!("req_{}", std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap_or_default()
@@ -199,92 +713,404 @@ impl WatchtowerMcpServer {
             request_id,
             prompt: request.prompt,
             options: request.options.unwrap_or_default(),
-        })
+        }).await
     }
 
     #[tool(description = "Set the status message displayed in the Watchtower toolbar")]
-    async fn set_status_message(&self, Parameters(request): Parameters<SetStatusMessageRequest>) -> String {
+    async fn set_status_message(&self, Parameters(request): Parameters<SetStatusMessageRequest>) -> Result<String, String> {
         self.send_command_and_wait(McpCommand::SetStatusMessage {
             message: request.message,
-        })
+        }).await
+    }
+
+    #[tool(description = "Wait for the given number of seconds (max 10) before returning. Use between scene commands to sequence timed actions.")]
+    async fn wait(&self, Parameters(request): Parameters<WaitRequest>) -> Result<String, String> {
+        if !request.seconds.is_finite() || request.seconds < 0.0 {
+            return Err("seconds must be a non-negative number".to_string());
+        }
+        let seconds = request.seconds.min(MAX_WAIT_SECONDS);
+        tokio::time::sleep(std::time::Duration::from_secs_f32(seconds)).await;
+        Ok(format!("Waited {seconds} seconds"))
     }
 
     #[tool(description = "Open a secondary 3D window with a camera and sun light. Use spawn_entity to add objects.")]
-    async fn open_3d_window(&self, Parameters(request): Parameters<Open3dWindowRequest>) -> String {
+    async fn open_3d_window(&self, Parameters(request): Parameters<Open3dWindowRequest>) -> Result<String, String> {
         self.send_command_and_wait(McpCommand::Open3dWindow {
             width: request.width.unwrap_or(800),
             height: request.height.unwrap_or(600),
-        })
+        }).await
     }
 
     #[tool(description = "Close the 3D window and clear all entities from the scene")]
-    async fn close_3d_window(&self) -> String {
-        self.send_command_and_wait(McpCommand::Close3dWindow)
+    async fn close_3d_window(&self) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::Close3dWindow).await
     }
 
-    #[tool(description = "Spawn a 3D primitive entity in the scene. Shapes: cube, sphere, cylinder, cone, torus, plane")]
-    async fn spawn_entity(&self, Parameters(request): Parameters<SpawnEntityRequest>) -> String {
+    #[tool(description = "Spawn a 3D primitive entity in the scene with an optional color, roughness, metallic, and emissive material. Shapes: cube, sphere, cylinder, cone, torus, plane")]
+    async fn spawn_entity(&self, Parameters(request): Parameters<SpawnEntityRequest>) -> Result<String, String> {
         self.send_command_and_wait(McpCommand::SpawnEntity {
             name: request.name,
             shape: request.shape,
             position: request.position,
             scale: request.scale.unwrap_or([1.0, 1.0, 1.0]),
-        })
+            material: MaterialOverrides {
+                color: request.color,
+                roughness: request.roughness,
+                metallic: request.metallic,
+                emissive: request.emissive,
+                texture: None,
+            },
+        }).await
+    }
+
+    #[tool(description = "Import a glTF/GLB model file as a single named entity in the 3D scene")]
+    async fn import_gltf_as_entity(&self, Parameters(request): Parameters<ImportGltfRequest>) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::ImportGltf {
+            name: request.name,
+            path: request.path,
+            position: request.position.unwrap_or([0.0, 0.0, 0.0]),
+            scale: request.scale.unwrap_or([1.0, 1.0, 1.0]),
+        }).await
+    }
+
+    #[tool(description = "Spawn a composite prefab (tree, house, character, fence) as several prefixed entities")]
+    async fn spawn_prefab(&self, Parameters(request): Parameters<SpawnPrefabRequest>) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::SpawnPrefab {
+            prefix: request.prefix.unwrap_or_else(|| request.name.clone()),
+            prefab: request.name,
+            position: request.position,
+            scale: request.scale.unwrap_or(1.0),
+        }).await
+    }
+
+    #[tool(description = "Make an entity pulse with a glow for a few seconds so the user can spot it in the 3D view")]
+    async fn highlight_entity(&self, Parameters(request): Parameters<HighlightEntityRequest>) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::HighlightEntity {
+            name: request.name,
+            duration_secs: request.duration_secs.unwrap_or(2.0),
+        }).await
+    }
+
+    #[tool(description = "Reload the Watchtower UI from site/dist on disk to pick up a fresh `trunk build`. Only available in dev mode (WATCHTOWER_DEV=1).")]
+    async fn reload_site(&self) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::ReloadSite).await
+    }
+
+    #[tool(description = "Get the saved Watchtower preferences and the effective values after environment variable overrides")]
+    async fn get_config(&self) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::GetConfig).await
+    }
+
+    #[tool(description = "Update and persist Watchtower preferences. Only the provided fields change.")]
+    async fn set_config(&self, Parameters(request): Parameters<SetConfigRequest>) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::SetConfig {
+            default_model: request.default_model,
+            cli_test_timeout_secs: request.cli_test_timeout_secs,
+            shadows_enabled: request.shadows_enabled,
+            keyboard_camera: request.keyboard_camera,
+            preserve_camera_on_assemble: request.preserve_camera_on_assemble,
+        }).await
     }
 
     #[tool(description = "Remove a named entity from the 3D scene")]
-    async fn remove_entity(&self, Parameters(request): Parameters<RemoveEntityRequest>) -> String {
+    async fn remove_entity(&self, Parameters(request): Parameters<RemoveEntityRequest>) -> Result<String, String> {
         self.send_command_and_wait(McpCommand::RemoveEntity {
             name: request.name,
-        })
+        }).await
     }
 
     #[tool(description = "Move a named entity to a new position")]
-    async fn move_entity(&self, Parameters(request): Parameters<MoveEntityRequest>) -> String {
+    async fn move_entity(&self, Parameters(request): Parameters<MoveEntityRequest>) -> Result<String, String> {
         self.send_command_and_wait(McpCommand::MoveEntity {
             name: request.name,
             position: request.position,
-        })
+        }).await
     }
 
     #[tool(description = "Set the rotation of a named entity using euler angles in degrees")]
-    async fn rotate_entity(&self, Parameters(request): Parameters<RotateEntityRequest>) -> String {
+    async fn rotate_entity(&self, Parameters(request): Parameters<RotateEntityRequest>) -> Result<String, String> {
         self.send_command_and_wait(McpCommand::RotateEntity {
             name: request.name,
             rotation: request.rotation,
-        })
+            snap_degrees: request.snap_degrees,
+        }).await
     }
 
     #[tool(description = "Set the scale of a named entity")]
-    async fn scale_entity(&self, Parameters(request): Parameters<ScaleEntityRequest>) -> String {
+    async fn scale_entity(&self, Parameters(request): Parameters<ScaleEntityRequest>) -> Result<String, String> {
         self.send_command_and_wait(McpCommand::ScaleEntity {
             name: request.name,
             scale: request.scale,
-        })
+        }).await
+    }
+
+    #[tool(description = "Lock or unlock an entity. Locked entities refuse move, rotate, scale and transform matrix changes, protecting fixed structure like a ground plane or camera rig.")]
+    async fn lock_entity(&self, Parameters(request): Parameters<LockEntityRequest>) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::SetLocked {
+            name: request.name,
+            locked: request.locked,
+        }).await
+    }
+
+    #[tool(description = "Apply a material preset and/or material values to many entities at once, selected by name list and/or name prefix. Unspecified values keep each entity's current material. Returns how many entities changed.")]
+    async fn apply_material(&self, Parameters(request): Parameters<ApplyMaterialRequest>) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::ApplyMaterial {
+            names: request.names.unwrap_or_default(),
+            prefix: request.prefix,
+            preset: request.preset,
+            material: MaterialOverrides {
+                color: request.color,
+                roughness: request.roughness,
+                metallic: request.metallic,
+                emissive: request.emissive,
+                texture: None,
+            },
+        }).await
+    }
+
+    #[tool(description = "Undo the most recent scene change: spawns, imports, removals, transforms, materials, and animations. Camera, lighting, grid, highlight, and lock changes are not undoable")]
+    async fn undo(&self) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::Undo).await
+    }
+
+    #[tool(description = "Redo the most recently undone scene change")]
+    async fn redo(&self) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::Redo).await
+    }
+
+    #[tool(description = "List the scene changes that can be undone and redone, most recent first")]
+    async fn get_history(&self) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::GetHistory).await
+    }
+
+    #[tool(description = "Move several named entities by the same offset in one call")]
+    async fn move_entities(&self, Parameters(request): Parameters<MoveEntitiesRequest>) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::MoveEntities {
+            names: request.names,
+            delta: request.delta,
+        }).await
+    }
+
+    #[tool(description = "Rotate several named entities as a group around their shared center")]
+    async fn rotate_entities(&self, Parameters(request): Parameters<RotateEntitiesRequest>) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::RotateEntities {
+            names: request.names,
+            degrees: request.degrees,
+        }).await
+    }
+
+    #[tool(description = "Scale several named entities as a group around their shared center")]
+    async fn scale_entities(&self, Parameters(request): Parameters<ScaleEntitiesRequest>) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::ScaleEntities {
+            names: request.names,
+            factor: request.factor,
+        }).await
     }
 
     #[tool(description = "Set the camera position by specifying focus point, distance (radius), yaw and pitch in degrees")]
-    async fn set_camera(&self, Parameters(request): Parameters<SetCameraRequest>) -> String {
+    async fn set_camera(&self, Parameters(request): Parameters<SetCameraRequest>) -> Result<String, String> {
         self.send_command_and_wait(McpCommand::SetCamera {
             focus: request.focus,
             radius: request.radius,
             yaw: request.yaw,
             pitch: request.pitch,
-        })
+        }).await
+    }
+
+    #[tool(description = "Make the camera keep a moving entity in focus, easing toward it every frame, e.g. for a third-person player. Pass a null target_name to stop.")]
+    async fn set_camera_follow(&self, Parameters(request): Parameters<SetCameraFollowRequest>) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::CameraFollow {
+            target_name: request.target_name,
+            offset: request.offset.unwrap_or([0.0, 0.0, 0.0]),
+            smoothing: request.smoothing.unwrap_or(5.0),
+        }).await
+    }
+
+    #[tool(description = "Set the local transform of a named entity from a column-major 4x4 matrix. The matrix must be invertible.")]
+    async fn set_transform_matrix(&self, Parameters(request): Parameters<SetTransformMatrixRequest>) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::SetTransformMatrix {
+            name: request.name,
+            matrix: request.matrix,
+        }).await
+    }
+
+    #[tool(description = "Get the local transform of a named entity as a column-major 4x4 matrix (16 floats)")]
+    async fn get_transform_matrix(&self, Parameters(request): Parameters<GetTransformMatrixRequest>) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::GetTransformMatrix {
+            name: request.name,
+        }).await
+    }
+
+    #[tool(description = "Return the name of the nearest entity under a screen coordinate in the 3D window, or null if nothing is hit")]
+    async fn get_entity_at(&self, Parameters(request): Parameters<PickEntityRequest>) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::PickEntity {
+            x: request.x,
+            y: request.y,
+        }).await
+    }
+
+    #[tool(description = "Enable or disable sun shadows and optionally set the shadow bias. Disabling shadows improves performance on weak GPUs.")]
+    async fn set_shadows(&self, Parameters(request): Parameters<SetShadowsRequest>) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::SetShadows {
+            enabled: request.enabled,
+            bias: request.bias,
+        }).await
+    }
+
+    #[tool(description = "Show or hide a reference grid on the ground plane (y = 0) to help judge positions. The grid is not a named entity and is excluded from list_entities.")]
+    async fn set_grid_floor(&self, Parameters(request): Parameters<SetGridFloorRequest>) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::SetGridFloor {
+            enabled: request.enabled,
+            size: request.size.unwrap_or(20.0),
+            spacing: request.spacing.unwrap_or(1.0),
+        }).await
+    }
+
+    #[tool(description = "Toggle a staggered reveal for spawned entities, scaling each one in from zero after the previous, for recorded \"watch it build\" demos. Off by default.")]
+    async fn set_spawn_animation(&self, Parameters(request): Parameters<SetSpawnAnimationRequest>) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::SetSpawnAnimation {
+            enabled: request.enabled,
+            per_entity_delay_ms: request.per_entity_delay_ms.unwrap_or(150),
+        }).await
+    }
+
+    #[tool(description = "Set an entity's opacity, keeping its color. Alpha below 1 renders the entity with alpha blending, e.g. for ghosts or fades.")]
+    async fn set_opacity(&self, Parameters(request): Parameters<SetOpacityRequest>) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::SetOpacity {
+            name: request.name,
+            alpha: request.alpha,
+        }).await
+    }
+
+    #[tool(description = "Apply an image as an entity's base color texture, keeping its other material settings")]
+    async fn set_texture(&self, Parameters(request): Parameters<SetTextureRequest>) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::SetTexture {
+            name: request.name,
+            texture_path: request.texture_path,
+        }).await
     }
 
-    #[tool(description = "List all named entities in the 3D scene with their positions")]
-    async fn list_entities(&self) -> String {
-        self.send_command_and_wait(McpCommand::ListEntities)
+    #[tool(description = "Make an entity's emissive glow pulse continuously, e.g. to draw attention to collectibles or objectives. Set enabled to false to stop it.")]
+    async fn set_entity_emissive_pulse(&self, Parameters(request): Parameters<SetEmissivePulseRequest>) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::SetEmissivePulse {
+            name: request.name,
+            color: request.color.unwrap_or([1.0, 0.8, 0.2]),
+            speed: request.speed.unwrap_or(1.0),
+            enabled: request.enabled.unwrap_or(true),
+        }).await
+    }
+
+    #[tool(description = "Move an entity in a continuous circle around a center point, e.g. for moons, rotating platforms, or orbiting hazards. Set speed_deg_per_sec to 0 to stop.")]
+    async fn set_entity_orbit(&self, Parameters(request): Parameters<SetOrbitRequest>) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::SetOrbit {
+            name: request.name,
+            center: request.center.unwrap_or([0.0, 0.0, 0.0]),
+            radius: request.radius,
+            speed_deg_per_sec: request.speed_deg_per_sec,
+            axis: request.axis.unwrap_or([0.0, 1.0, 0.0]),
+        }).await
+    }
+
+    #[tool(description = "Give an entity a constant velocity, optionally with gravity and bouncing on the ground, e.g. for projectiles or falling objects")]
+    async fn set_velocity(&self, Parameters(request): Parameters<SetVelocityRequest>) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::SetVelocity {
+            name: request.name,
+            velocity: request.velocity,
+            gravity: request.gravity.unwrap_or(false),
+            bounciness: request.bounciness.unwrap_or(0.5),
+        }).await
+    }
+
+    #[tool(description = "Leave a fading trail of glowing segments behind a moving entity, e.g. for comets or projectiles")]
+    async fn set_entity_trail(&self, Parameters(request): Parameters<SetTrailRequest>) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::SetTrail {
+            name: request.name,
+            length: request.length.unwrap_or(20),
+            color: request.color.unwrap_or([1.0, 0.8, 0.2]),
+            enabled: request.enabled,
+        }).await
+    }
+
+    #[tool(description = "Record a timeline keyframe for an entity's position, rotation, and scale. Use seek to pose every keyframed entity at a given time.")]
+    async fn add_keyframe(&self, Parameters(request): Parameters<AddKeyframeRequest>) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::AddKeyframe {
+            name: request.name,
+            time: request.time,
+            position: request.position,
+            rotation: request.rotation,
+            scale: request.scale,
+        }).await
+    }
+
+    #[tool(description = "Scrub the keyframe timeline to a time in seconds, interpolating every keyframed entity's transform and applying it immediately")]
+    async fn seek(&self, Parameters(request): Parameters<SeekRequest>) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::Seek {
+            time: request.time,
+        }).await
+    }
+
+    #[tool(description = "Make an entity always face the camera, e.g. for name tags, health bars, or 2D sprites in the 3D scene")]
+    async fn set_billboard(&self, Parameters(request): Parameters<SetBillboardRequest>) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::SetBillboard {
+            name: request.name,
+            enabled: request.enabled,
+        }).await
+    }
+
+    #[tool(description = "List every active per-frame animation (orbits, emissive pulses, velocities, billboards, highlights, keyframes, and trails) with its entity and parameters")]
+    async fn list_animations(&self) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::ListAnimations).await
+    }
+
+    #[tool(description = "Stop orbits, emissive pulses, velocities, billboards, highlights, keyframes, and trails on one entity, or on every entity when no name is given")]
+    async fn clear_animations(&self, Parameters(request): Parameters<ClearAnimationsRequest>) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::ClearAnimations { name: request.name }).await
+    }
+
+    #[tool(description = "Inspect entity counts, spawn rate, shadow settings, and frame time history, and return concrete suggestions for improving scene performance")]
+    async fn analyze_performance(&self) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::AnalyzePerformance).await
+    }
+
+    #[tool(description = "Get recent per-second samples of frame time, named entity count, and spawns. A rising entity count or frame time over the series points to unbounded spawning or a slowdown over time.")]
+    async fn get_frame_stats(&self, Parameters(request): Parameters<GetFrameStatsRequest>) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::GetFrameStats { count: request.count }).await
+    }
+
+    #[tool(description = "List named entities in the 3D scene with their positions, sorted by name. Returns a page with the total count; use offset/limit to page through large scenes.")]
+    async fn list_entities(&self, Parameters(request): Parameters<ListEntitiesRequest>) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::ListEntities {
+            offset: request.offset.unwrap_or(0),
+            limit: request.limit,
+        }).await
+    }
+
+    #[tool(description = "List the canonical values accepted by scene tools, such as valid spawn_entity shapes")]
+    async fn get_capabilities(&self) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::GetCapabilities).await
+    }
+
+    #[tool(description = "Get the Watchtower version, UI protocol version, and MCP port")]
+    async fn get_version(&self) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::GetVersion).await
+    }
+
+    #[tool(description = "Get the axis-aligned bounds of all entities in the scene as {min, max, center, size}, or null when the scene is empty")]
+    async fn get_scene_bounds(&self) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::GetSceneBounds).await
+    }
+
+    #[tool(description = "Get the current lighting: the sun's direction, intensity, color, and shadow settings, plus any lights attached to named entities. Use this to reason about why a scene looks too dark or too bright.")]
+    async fn get_lighting(&self) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::GetLighting).await
     }
 
     #[tool(description = "Remove all spawned entities from the scene (keeps camera and sun)")]
-    async fn clear_scene(&self) -> String {
-        self.send_command_and_wait(McpCommand::ClearScene)
+    async fn clear_scene(&self) -> Result<String, String> {
+        self.send_command_and_wait(McpCommand::ClearScene).await
     }
 }
 
-#[tool_handler]
 impl ServerHandler for WatchtowerMcpServer {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
@@ -295,33 +1121,78 @@ impl ServerHandler for WatchtowerMcpServer {
             ..Default::default()
         }
     }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, McpError> {
+        Ok(ListToolsResult {
+            tools: self.tool_router.list_all(),
+            next_cursor: None,
+        })
+    }
+
+    async fn call_tool(
+        &self,
+        request: CallToolRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let tool = self.tool_router.list_all().into_iter().find(|tool| tool.name == request.name);
+        if let Some(tool) = tool {
+            let arguments = request.arguments.clone().unwrap_or_default();
+            if let Err(message) = validate_arguments(&request.name, &tool.input_schema, &arguments) {
+                self.report_tool_error(&request.name, &message);
+                return Ok(CallToolResult::error(vec![Content::text(message)]));
+            }
+        }
+        let cancelled = context.ct.clone();
+        tokio::select! {
+            result = self.tool_router.call(ToolCallContext::new(self, request, context)) => result,
+            _ = cancelled.cancelled() => Ok(CallToolResult::error(vec![Content::text("Tool call cancelled by the client")])),
+        }
+    }
 }
 
 pub fn start_watchtower_mcp_server(
     command_queue: WatchtowerCommandQueue,
     response_queue: WatchtowerResponseQueue,
+    shutdown: Arc<AtomicBool>,
 ) {
     std::thread::spawn(move || {
         let runtime = tokio::runtime::Runtime::new().unwrap();
         runtime.block_on(async {
             let command_queue_clone = command_queue.clone();
             let response_queue_clone = response_queue.clone();
+            let shutdown_clone = shutdown.clone();
 
             let service = StreamableHttpService::new(
-                move || Ok(WatchtowerMcpServer::new(command_queue_clone.clone(), response_queue_clone.clone())),
+                move || Ok(WatchtowerMcpServer::new(
+                    command_queue_clone.clone(),
+                    response_queue_clone.clone(),
+                    shutdown_clone.clone(),
+                )),
                 LocalSessionManager::default().into(),
                 Default::default(),
             );
 
             let router = axum::Router::new().nest_service("/mcp", service);
-            let tcp_listener = tokio::net::TcpListener::bind("127.0.0.1:3334").await.unwrap();
+            let tcp_listener = tokio::net::TcpListener::bind(("127.0.0.1", MCP_PORT)).await.unwrap();
 
-            eprintln!("Watchtower MCP server listening on http://127.0.0.1:3334/mcp");
-            eprintln!("Add to Claude Code: claude mcp add --transport http watchtower http://127.0.0.1:3334/mcp");
+            eprintln!("Watchtower MCP server listening on http://127.0.0.1:{MCP_PORT}/mcp");
+            eprintln!("Add to Claude Code: claude mcp add --transport http watchtower http://127.0.0.1:{MCP_PORT}/mcp");
 
             axum::serve(tcp_listener, router)
-                .with_graceful_shutdown(async {
-                    tokio::signal::ctrl_c().await.ok();
+                .with_graceful_shutdown(async move {
+                    tokio::select! {
+                        _ = tokio::signal::ctrl_c() => {}
+                        _ = async {
+                            while !shutdown.load(Ordering::SeqCst) {
+                                tokio::time::sleep(RESPONSE_POLL_INTERVAL).await;
+                            }
+                        } => {}
+                    }
+                    shutdown.store(true, Ordering::SeqCst);
                 })
                 .await
                 .ok();
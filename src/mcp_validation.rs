@@ -0,0 +1,133 @@
+use serde_json::{Map, Value};
+
+pub fn validate_arguments(tool_name: &str, schema: &Map<String, Value>, arguments: &Map<String, Value>) -> Result<(), String> {
+    let empty = Map::new();
+    let properties = schema.get("properties").and_then(Value::as_object).unwrap_or(&empty);
+    let required: Vec<&str> = schema.get("required")
+        .and_then(Value::as_array)
+        .map(|fields| fields.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    let mut problems = Vec::new();
+    for field in &required {
+        if !arguments.contains_key(*field) {
+            let expected = properties.get(*field).map(describe_schema).unwrap_or_else(|| "a value".to_string());
+            problems.push(format!("missing required field '{field}' (expected {expected})"));
+        }
+    }
+    for (field, value) in arguments {
+        if let Some(field_schema) = properties.get(field)
+            && let Err(problem) = check_value(field_schema, value)
+        {
+            problems.push(format!("field '{field}': {problem}"));
+        }
+    }
+    if problems.is_empty() {
+        return Ok(());
+    }
+
+    let unknown: Vec<&str> = arguments.keys()
+        .map(String::as_str)
+        .filter(|field| !properties.contains_key(*field))
+        .collect();
+    if !unknown.is_empty() {
+        problems.push(format!("unknown fields are ignored: {}", unknown.join(", ")));
+    }
+
+    let mut fields: Vec<String> = properties.iter().map(|(field, field_schema)| {
+        let presence = if required.contains(&field.as_str()) { "required" } else { "optional" };
+        format!("{field} ({}, {presence})", describe_schema(field_schema))
+    }).collect();
+    fields.sort();
+    Err(format!(
+        "Invalid arguments for {tool_name}: {}. Expected fields: {}",
+        problems.join("; "),
+        fields.join(", ")
+    ))
+}
+
+fn schema_types(schema: &Value) -> Vec<&str> {
+    match schema.get("type") {
+        Some(Value::String(kind)) => vec![kind.as_str()],
+        Some(Value::Array(kinds)) => kinds.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn matches_type(kind: &str, value: &Value) -> bool {
+    match kind {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "string" => value.is_string(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        _ => true,
+    }
+}
+
+fn check_value(schema: &Value, value: &Value) -> Result<(), String> {
+    let types = schema_types(schema);
+    if types.is_empty() {
+        return Ok(());
+    }
+    if !types.iter().any(|kind| matches_type(kind, value)) {
+        return Err(format!("expected {}, got {}", describe_schema(schema), describe_value(value)));
+    }
+
+    if let (Some(minimum), Some(number)) = (schema.get("minimum").and_then(Value::as_f64), value.as_f64())
+        && number < minimum
+    {
+        return Err(format!("expected {} of at least {minimum}, got {number}", describe_schema(schema)));
+    }
+
+    if let Value::Array(items) = value {
+        let min_items = schema.get("minItems").and_then(Value::as_u64).unwrap_or(0) as usize;
+        let max_items = schema.get("maxItems").and_then(Value::as_u64).map(|max| max as usize);
+        if items.len() < min_items || max_items.is_some_and(|max| items.len() > max) {
+            return Err(format!("expected {}, got {}", describe_schema(schema), describe_value(value)));
+        }
+        if let Some(item_schema) = schema.get("items") {
+            for (index, item) in items.iter().enumerate() {
+                check_value(item_schema, item).map_err(|problem| format!("item {index}: {problem}"))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn describe_schema(schema: &Value) -> String {
+    let types = schema_types(schema);
+    let kind = types.iter().find(|kind| **kind != "null").copied().unwrap_or("any");
+    match kind {
+        "array" => {
+            let item = schema.get("items").map(describe_schema).unwrap_or_else(|| "value".to_string());
+            let item = item.trim_start_matches("a ").trim_start_matches("an ");
+            let min_items = schema.get("minItems").and_then(Value::as_u64);
+            let max_items = schema.get("maxItems").and_then(Value::as_u64);
+            match (min_items, max_items) {
+                (Some(min), Some(max)) if min == max => format!("an array of exactly {min} {item}s"),
+                _ => format!("an array of {item}s"),
+            }
+        }
+        "number" => "a number".to_string(),
+        "integer" => "a whole number".to_string(),
+        "string" => "a string".to_string(),
+        "boolean" => "true or false".to_string(),
+        "object" => "an object".to_string(),
+        _ => "a value".to_string(),
+    }
+}
+
+fn describe_value(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(flag) => format!("{flag}"),
+        Value::Number(number) => format!("the number {number}"),
+        Value::String(text) if text.len() > 40 => "a string".to_string(),
+        Value::String(text) => format!("the string \"{text}\""),
+        Value::Array(items) => format!("an array of {} items", items.len()),
+        Value::Object(_) => "an object".to_string(),
+    }
+}
@@ -1,12 +1,385 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use nightshade::ecs::prefab::{import_gltf_from_path, spawn_prefab_with_animations};
 use nightshade::prelude::*;
 
+use crate::history::History;
+use crate::mcp_server::MaterialOverrides;
+
+pub const SHAPES: &[&str] = &["cube", "sphere", "cylinder", "cone", "torus", "plane"];
+
+const SHAPE_HALF_EXTENTS: &[(&str, [f32; 3])] = &[
+    ("cube", [0.5, 0.5, 0.5]),
+    ("sphere", [0.5, 0.5, 0.5]),
+    ("cylinder", [0.5, 0.5, 0.5]),
+    ("cone", [0.5, 0.5, 0.5]),
+    ("torus", [0.75, 0.25, 0.75]),
+    ("plane", [0.5, 0.0, 0.5]),
+];
+
+pub fn shape_half_extents(shape: &str) -> nalgebra_glm::Vec3 {
+    let extents = SHAPE_HALF_EXTENTS.iter()
+        .find(|(name, _)| *name == shape)
+        .map(|(_, extents)| *extents)
+        .unwrap_or([0.5, 0.5, 0.5]);
+    nalgebra_glm::Vec3::new(extents[0], extents[1], extents[2])
+}
+
+pub fn spawn_shape_at(world: &mut World, shape: &str, position: nalgebra_glm::Vec3) -> Option<Entity> {
+    match shape {
+        "cube" => Some(spawn_cube_at(world, position)),
+        "sphere" => Some(spawn_sphere_at(world, position)),
+        "cylinder" => Some(spawn_cylinder_at(world, position)),
+        "cone" => Some(spawn_cone_at(world, position)),
+        "torus" => Some(spawn_torus_at(world, position)),
+        "plane" => Some(spawn_plane_at(world, position)),
+        _ => None,
+    }
+}
+
+pub struct ImportedGltf {
+    pub entity: Entity,
+    pub meshes: usize,
+    pub textures: usize,
+}
+
+pub fn spawn_gltf_at(world: &mut World, path: &str, position: nalgebra_glm::Vec3) -> Result<ImportedGltf, String> {
+    let gltf_path = std::path::Path::new(path);
+    if !gltf_path.is_file() {
+        return Err(format!("glTF file '{path}' not found"));
+    }
+    let result = import_gltf_from_path(gltf_path).map_err(|error| format!("failed to load glTF '{path}': {error}"))?;
+    let prefab = result.prefabs.first().ok_or_else(|| format!("glTF '{path}' contains no scenes"))?;
+
+    for (texture_name, (rgba_data, width, height)) in &result.textures {
+        world.queue_command(WorldCommand::LoadTexture {
+            name: texture_name.clone(),
+            rgba_data: rgba_data.clone(),
+            width: *width,
+            height: *height,
+        });
+    }
+
+    let entity = spawn_prefab_with_animations(world, prefab, &result.animations, position);
+    Ok(ImportedGltf {
+        entity,
+        meshes: result.meshes.len(),
+        textures: result.textures.len(),
+    })
+}
+
+pub struct PrefabPart {
+    pub suffix: &'static str,
+    pub shape: &'static str,
+    pub offset: [f32; 3],
+    pub scale: [f32; 3],
+    pub color: [f32; 4],
+}
+
+pub struct Prefab {
+    pub name: &'static str,
+    pub parts: &'static [PrefabPart],
+}
+
+const BARK: [f32; 4] = [0.4, 0.26, 0.13, 1.0];
+const FOLIAGE: [f32; 4] = [0.2, 0.55, 0.2, 1.0];
+const WALL: [f32; 4] = [0.85, 0.8, 0.7, 1.0];
+const ROOF: [f32; 4] = [0.6, 0.2, 0.15, 1.0];
+const SKIN: [f32; 4] = [0.9, 0.72, 0.6, 1.0];
+const CLOTH: [f32; 4] = [0.2, 0.35, 0.7, 1.0];
+const WOOD: [f32; 4] = [0.55, 0.4, 0.25, 1.0];
+const GRID_LINE: [f32; 4] = [0.35, 0.35, 0.4, 1.0];
+const GRID_AXIS: [f32; 4] = [0.7, 0.7, 0.75, 1.0];
+const GRID_LINE_THICKNESS: f32 = 0.02;
+pub const MAX_GRID_LINES_PER_AXIS: usize = 201;
+
+pub const PREFABS: &[Prefab] = &[
+    Prefab {
+        name: "tree",
+        parts: &[
+            PrefabPart { suffix: "trunk", shape: "cylinder", offset: [0.0, 0.5, 0.0], scale: [0.25, 1.0, 0.25], color: BARK },
+            PrefabPart { suffix: "foliage", shape: "cone", offset: [0.0, 1.75, 0.0], scale: [1.0, 1.5, 1.0], color: FOLIAGE },
+        ],
+    },
+    Prefab {
+        name: "house",
+        parts: &[
+            PrefabPart { suffix: "walls", shape: "cube", offset: [0.0, 0.75, 0.0], scale: [2.0, 1.5, 2.0], color: WALL },
+            PrefabPart { suffix: "roof", shape: "cone", offset: [0.0, 2.0, 0.0], scale: [2.6, 1.0, 2.6], color: ROOF },
+            PrefabPart { suffix: "door", shape: "cube", offset: [0.0, 0.45, 1.01], scale: [0.5, 0.9, 0.05], color: WOOD },
+        ],
+    },
+    Prefab {
+        name: "character",
+        parts: &[
+            PrefabPart { suffix: "leg_left", shape: "cylinder", offset: [-0.15, 0.4, 0.0], scale: [0.15, 0.8, 0.15], color: CLOTH },
+            PrefabPart { suffix: "leg_right", shape: "cylinder", offset: [0.15, 0.4, 0.0], scale: [0.15, 0.8, 0.15], color: CLOTH },
+            PrefabPart { suffix: "body", shape: "cube", offset: [0.0, 1.1, 0.0], scale: [0.6, 0.7, 0.3], color: CLOTH },
+            PrefabPart { suffix: "head", shape: "sphere", offset: [0.0, 1.7, 0.0], scale: [0.4, 0.4, 0.4], color: SKIN },
+        ],
+    },
+    Prefab {
+        name: "fence",
+        parts: &[
+            PrefabPart { suffix: "post_left", shape: "cube", offset: [-1.0, 0.5, 0.0], scale: [0.15, 1.0, 0.15], color: WOOD },
+            PrefabPart { suffix: "post_right", shape: "cube", offset: [1.0, 0.5, 0.0], scale: [0.15, 1.0, 0.15], color: WOOD },
+            PrefabPart { suffix: "rail_top", shape: "cube", offset: [0.0, 0.8, 0.0], scale: [2.0, 0.1, 0.08], color: WOOD },
+            PrefabPart { suffix: "rail_bottom", shape: "cube", offset: [0.0, 0.4, 0.0], scale: [2.0, 0.1, 0.08], color: WOOD },
+        ],
+    },
+];
+
+const LAYOUT_JITTER: f32 = 0.5;
+
+pub struct LayoutJitter {
+    state: u64,
+}
+
+impl LayoutJitter {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next_unit(&mut self) -> f32 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut value = self.state;
+        value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        value ^= value >> 31;
+        (value >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    pub fn offset(&mut self) -> [f32; 2] {
+        [
+            (self.next_unit() * 2.0 - 1.0) * LAYOUT_JITTER,
+            (self.next_unit() * 2.0 - 1.0) * LAYOUT_JITTER,
+        ]
+    }
+}
+
+pub fn find_prefab(name: &str) -> Option<&'static Prefab> {
+    PREFABS.iter().find(|prefab| prefab.name == name)
+}
+
+pub struct MaterialPreset {
+    pub name: &'static str,
+    pub color: [f32; 4],
+    pub roughness: f32,
+    pub metallic: f32,
+    pub emissive: Option<[f32; 3]>,
+}
+
+impl MaterialPreset {
+    pub fn overrides(&self) -> MaterialOverrides {
+        MaterialOverrides {
+            color: Some(self.color),
+            roughness: Some(self.roughness),
+            metallic: Some(self.metallic),
+            emissive: self.emissive,
+            texture: None,
+        }
+    }
+}
+
+pub const MATERIAL_PRESETS: &[MaterialPreset] = &[
+    MaterialPreset { name: "gold", color: [1.0, 0.77, 0.34, 1.0], roughness: 0.3, metallic: 1.0, emissive: None },
+    MaterialPreset { name: "chrome", color: [0.9, 0.9, 0.92, 1.0], roughness: 0.05, metallic: 1.0, emissive: None },
+    MaterialPreset { name: "copper", color: [0.95, 0.64, 0.54, 1.0], roughness: 0.35, metallic: 1.0, emissive: None },
+    MaterialPreset { name: "plastic", color: [0.8, 0.8, 0.8, 1.0], roughness: 0.4, metallic: 0.0, emissive: None },
+    MaterialPreset { name: "rubber", color: [0.1, 0.1, 0.1, 1.0], roughness: 0.95, metallic: 0.0, emissive: None },
+    MaterialPreset { name: "brick", color: [0.6, 0.25, 0.18, 1.0], roughness: 0.9, metallic: 0.0, emissive: None },
+    MaterialPreset { name: "glass", color: [0.8, 0.9, 1.0, 0.3], roughness: 0.05, metallic: 0.0, emissive: None },
+    MaterialPreset { name: "neon", color: [0.1, 0.9, 1.0, 1.0], roughness: 0.5, metallic: 0.0, emissive: Some([0.4, 3.6, 4.0]) },
+];
+
+pub fn find_material_preset(name: &str) -> Option<&'static MaterialPreset> {
+    MATERIAL_PRESETS.iter().find(|preset| preset.name == name)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct EmissivePulse {
+    pub color: [f32; 3],
+    pub speed: f32,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct Orbit {
+    pub center: nalgebra_glm::Vec3,
+    pub axis: nalgebra_glm::Vec3,
+    pub reference: nalgebra_glm::Vec3,
+    pub radius: f32,
+    pub speed_deg_per_sec: f32,
+    pub angle_deg: f32,
+}
+
+impl Orbit {
+    pub fn new(center: nalgebra_glm::Vec3, axis: nalgebra_glm::Vec3, start: nalgebra_glm::Vec3, radius: f32, speed_deg_per_sec: f32) -> Option<Self> {
+        if nalgebra_glm::length(&axis) < f32::EPSILON {
+            return None;
+        }
+        let axis = nalgebra_glm::normalize(&axis);
+        let offset = start - center;
+        let planar = offset - axis * nalgebra_glm::dot(&offset, &axis);
+        let reference = if nalgebra_glm::length(&planar) > 1e-4 {
+            nalgebra_glm::normalize(&planar)
+        } else {
+            let helper = if axis.x.abs() < 0.9 {
+                nalgebra_glm::Vec3::new(1.0, 0.0, 0.0)
+            } else {
+                nalgebra_glm::Vec3::new(0.0, 0.0, 1.0)
+            };
+            nalgebra_glm::normalize(&nalgebra_glm::cross(&axis, &helper))
+        };
+        Some(Self {
+            center,
+            axis,
+            reference,
+            radius,
+            speed_deg_per_sec,
+            angle_deg: 0.0,
+        })
+    }
+
+    pub fn advance(&mut self, delta_seconds: f32) -> nalgebra_glm::Vec3 {
+        self.angle_deg = (self.angle_deg + self.speed_deg_per_sec * delta_seconds) % 360.0;
+        let rotation = nalgebra_glm::quat_angle_axis(self.angle_deg.to_radians(), &self.axis);
+        self.center + nalgebra_glm::quat_rotate_vec3(&rotation, &(self.reference * self.radius))
+    }
+}
+
+const GRAVITY: f32 = -9.81;
+const MIN_BOUNCE_SPEED: f32 = 0.5;
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct Velocity {
+    pub linear: nalgebra_glm::Vec3,
+    pub gravity: bool,
+    pub bounciness: f32,
+}
+
+impl Velocity {
+    pub fn advance(&mut self, translation: &mut nalgebra_glm::Vec3, ground_height: f32, delta_seconds: f32) {
+        if self.gravity {
+            self.linear.y += GRAVITY * delta_seconds;
+        }
+        *translation += self.linear * delta_seconds;
+        if self.gravity && translation.y < ground_height {
+            translation.y = ground_height;
+            self.linear.y = -self.linear.y * self.bounciness;
+            if self.linear.y.abs() < MIN_BOUNCE_SPEED {
+                self.linear.y = 0.0;
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct CameraFollow {
+    pub target: String,
+    pub offset: nalgebra_glm::Vec3,
+    pub smoothing: f32,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct CameraPose {
+    pub focus: nalgebra_glm::Vec3,
+    pub radius: f32,
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+pub const MAX_TRAIL_LENGTH: usize = 64;
+const TRAIL_SEGMENT_SPACING: f32 = 0.15;
+const TRAIL_SEGMENT_SCALE: f32 = 0.2;
+
+pub struct Trail {
+    pub length: usize,
+    pub color: [f32; 3],
+    pub segments: VecDeque<Entity>,
+    pub last_position: Option<nalgebra_glm::Vec3>,
+}
+
+impl Trail {
+    pub fn new(length: usize, color: [f32; 3]) -> Self {
+        Self {
+            length: length.clamp(1, MAX_TRAIL_LENGTH),
+            color,
+            segments: VecDeque::new(),
+            last_position: None,
+        }
+    }
+
+    pub fn despawn(self, world: &mut World) {
+        for segment in self.segments {
+            despawn_recursive_immediate(world, segment);
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct Keyframe {
+    pub time: f32,
+    pub translation: nalgebra_glm::Vec3,
+    pub rotation: nalgebra_glm::Quat,
+    pub scale: nalgebra_glm::Vec3,
+}
+
+pub fn sample_keyframes(keyframes: &[Keyframe], time: f32) -> Option<Keyframe> {
+    let first = keyframes.first()?;
+    let last = keyframes.last()?;
+    if time <= first.time {
+        return Some(*first);
+    }
+    if time >= last.time {
+        return Some(*last);
+    }
+    let next_index = keyframes.iter().position(|keyframe| keyframe.time > time)?;
+    let previous = keyframes[next_index - 1];
+    let next = keyframes[next_index];
+    let blend = (time - previous.time) / (next.time - previous.time);
+    Some(Keyframe {
+        time,
+        translation: nalgebra_glm::lerp(&previous.translation, &next.translation, blend),
+        rotation: nalgebra_glm::quat_slerp(&previous.rotation, &next.rotation, blend),
+        scale: nalgebra_glm::lerp(&previous.scale, &next.scale, blend),
+    })
+}
+
+const SPAWN_REVEAL_SECONDS: f32 = 0.35;
+
+pub struct SpawnReveal {
+    pub name: String,
+    pub target_scale: nalgebra_glm::Vec3,
+    pub elapsed: f32,
+}
+
 #[derive(Default)]
 pub struct SceneState {
     pub window_count: u32,
+    pub window_size: [u32; 2],
     pub camera_entity: Option<Entity>,
     pub sun_entity: Option<Entity>,
-    pub entities: HashMap<String, Entity>,
+    pub shadows_enabled: Option<bool>,
+    pub shadow_bias: Option<f32>,
+    pub entities: BTreeMap<String, Entity>,
+    pub materials: HashMap<String, MaterialOverrides>,
+    pub highlights: HashMap<String, f32>,
+    pub emissive_pulses: HashMap<String, EmissivePulse>,
+    pub orbits: HashMap<String, Orbit>,
+    pub velocities: HashMap<String, Velocity>,
+    pub billboards: HashSet<String>,
+    pub camera_follow: Option<CameraFollow>,
+    pub framed_camera: Option<CameraPose>,
+    pub keyframes: HashMap<String, Vec<Keyframe>>,
+    pub trails: HashMap<String, Trail>,
+    pub locked: HashSet<String>,
+    pub shapes: HashMap<String, String>,
+    pub gltf_sources: HashMap<String, String>,
+    pub grid_entities: Vec<Entity>,
+    pub spawn_reveal_delay: Option<f32>,
+    pub pending_reveals: VecDeque<SpawnReveal>,
+    pub active_reveals: Vec<SpawnReveal>,
+    pub reveal_timer: f32,
+    pub history: History,
 }
 
 impl SceneState {
@@ -18,16 +391,570 @@ impl SceneState {
         for window_state in &mut world.resources.secondary_windows.states {
             window_state.close_requested = true;
         }
-        for (_name, entity) in self.entities.drain() {
-            despawn_recursive_immediate(world, entity);
-        }
+        self.clear_entities(world);
+        self.clear_grid_floor(world);
+        self.history.clear();
         if let Some(camera) = self.camera_entity.take() {
             despawn_recursive_immediate(world, camera);
         }
+        self.framed_camera = None;
         if let Some(sun) = self.sun_entity.take() {
             despawn_recursive_immediate(world, sun);
         }
         world.resources.active_camera = None;
         self.window_count = 0;
     }
+
+    pub fn is_animating(&self) -> bool {
+        let moving = |name: &String| !self.locked.contains(name);
+        self.orbits.keys().any(moving)
+            || self.velocities.keys().any(moving)
+            || !self.highlights.is_empty()
+            || !self.emissive_pulses.is_empty()
+            || !self.pending_reveals.is_empty()
+            || !self.active_reveals.is_empty()
+            || self.camera_follow.is_some()
+    }
+
+    pub fn camera_pose(&self, world: &World) -> Option<CameraPose> {
+        let pan_orbit = world.get_pan_orbit_camera(self.camera_entity?)?;
+        Some(CameraPose {
+            focus: pan_orbit.target_focus,
+            radius: pan_orbit.target_radius,
+            yaw: pan_orbit.target_yaw,
+            pitch: pan_orbit.target_pitch,
+        })
+    }
+
+    pub fn set_camera_pose(&self, world: &mut World, pose: CameraPose) {
+        if let Some(camera) = self.camera_entity
+            && let Some(pan_orbit) = world.get_pan_orbit_camera_mut(camera)
+        {
+            pan_orbit.target_focus = pose.focus;
+            pan_orbit.target_radius = pose.radius;
+            pan_orbit.target_yaw = pose.yaw;
+            pan_orbit.target_pitch = pose.pitch;
+        }
+    }
+
+    pub fn frame_camera(&mut self, world: &World) {
+        self.framed_camera = self.camera_pose(world);
+    }
+
+    pub fn camera_touched(&self, world: &World) -> bool {
+        self.framed_camera.is_some() && self.camera_pose(world) != self.framed_camera
+    }
+
+    pub fn insert_keyframe(&mut self, name: &str, keyframe: Keyframe) -> usize {
+        let keyframes = self.keyframes.entry(name.to_string()).or_default();
+        keyframes.retain(|existing| (existing.time - keyframe.time).abs() > f32::EPSILON);
+        let index = keyframes.partition_point(|existing| existing.time < keyframe.time);
+        keyframes.insert(index, keyframe);
+        keyframes.len()
+    }
+
+    pub fn update_trails(&mut self, world: &mut World) {
+        let orphaned: Vec<String> = self.trails.keys()
+            .filter(|name| !self.entities.contains_key(*name))
+            .cloned()
+            .collect();
+        for name in orphaned {
+            if let Some(trail) = self.trails.remove(&name) {
+                trail.despawn(world);
+            }
+        }
+
+        for (name, trail) in &mut self.trails {
+            let Some(position) = self.entities.get(name)
+                .and_then(|&entity| world.get_local_transform(entity))
+                .map(|transform| transform.translation)
+            else {
+                continue;
+            };
+            let Some(last_position) = trail.last_position else {
+                trail.last_position = Some(position);
+                continue;
+            };
+            if nalgebra_glm::distance(&last_position, &position) < TRAIL_SEGMENT_SPACING {
+                continue;
+            }
+            trail.last_position = Some(position);
+
+            let segment = spawn_sphere_at(world, last_position);
+            let [red, green, blue] = trail.color;
+            let overrides = MaterialOverrides {
+                color: Some([red, green, blue, 1.0]),
+                emissive: Some(trail.color),
+                ..Default::default()
+            };
+            write_entity_material(world, segment, &format!("trail_{name}"), &overrides);
+            trail.segments.push_back(segment);
+            while trail.segments.len() > trail.length {
+                if let Some(oldest) = trail.segments.pop_front() {
+                    despawn_recursive_immediate(world, oldest);
+                }
+            }
+
+            for (age, &segment) in trail.segments.iter().rev().enumerate() {
+                let fade = 1.0 - age as f32 / trail.length as f32;
+                if let Some(transform) = world.get_local_transform_mut(segment) {
+                    transform.scale = nalgebra_glm::Vec3::repeat(TRAIL_SEGMENT_SCALE * fade);
+                }
+                world.set_local_transform_dirty(segment, LocalTransformDirty);
+            }
+        }
+    }
+
+    pub fn clear_entities(&mut self, world: &mut World) -> usize {
+        let count = self.entities.len();
+        for (_name, entity) in std::mem::take(&mut self.entities) {
+            despawn_recursive_immediate(world, entity);
+        }
+        self.materials.clear();
+        self.highlights.clear();
+        self.emissive_pulses.clear();
+        self.orbits.clear();
+        self.velocities.clear();
+        self.billboards.clear();
+        self.keyframes.clear();
+        for (_name, trail) in std::mem::take(&mut self.trails) {
+            trail.despawn(world);
+        }
+        self.camera_follow = None;
+        self.locked.clear();
+        self.shapes.clear();
+        self.gltf_sources.clear();
+        self.pending_reveals.clear();
+        self.active_reveals.clear();
+        count
+    }
+
+    pub fn queue_spawn_reveal(&mut self, world: &mut World, name: &str, entity: Entity) {
+        if self.spawn_reveal_delay.is_none() {
+            return;
+        }
+        let Some(transform) = world.get_local_transform_mut(entity) else {
+            return;
+        };
+        let target_scale = transform.scale;
+        transform.scale = nalgebra_glm::Vec3::zeros();
+        world.set_local_transform_dirty(entity, LocalTransformDirty);
+        self.pending_reveals.push_back(SpawnReveal {
+            name: name.to_string(),
+            target_scale,
+            elapsed: 0.0,
+        });
+    }
+
+    pub fn update_spawn_reveals(&mut self, world: &mut World, delta_seconds: f32) {
+        let delay = self.spawn_reveal_delay.unwrap_or(0.0);
+        if self.pending_reveals.is_empty() {
+            self.reveal_timer = 0.0;
+        } else {
+            self.reveal_timer -= delta_seconds;
+            while self.reveal_timer <= 0.0 {
+                let Some(reveal) = self.pending_reveals.pop_front() else {
+                    break;
+                };
+                self.active_reveals.push(reveal);
+                self.reveal_timer += delay;
+            }
+        }
+
+        let entities = &self.entities;
+        self.active_reveals.retain_mut(|reveal| {
+            let Some(&entity) = entities.get(&reveal.name) else {
+                return false;
+            };
+            reveal.elapsed += delta_seconds;
+            let progress = (reveal.elapsed / SPAWN_REVEAL_SECONDS).min(1.0);
+            let eased = 1.0 - (1.0 - progress).powi(3);
+            if let Some(transform) = world.get_local_transform_mut(entity) {
+                transform.scale = reveal.target_scale * eased;
+            }
+            world.set_local_transform_dirty(entity, LocalTransformDirty);
+            progress < 1.0
+        });
+    }
+
+    pub fn finish_spawn_reveals(&mut self, world: &mut World) {
+        let reveals: Vec<SpawnReveal> = self.pending_reveals.drain(..).chain(self.active_reveals.drain(..)).collect();
+        for reveal in reveals {
+            let Some(&entity) = self.entities.get(&reveal.name) else {
+                continue;
+            };
+            if let Some(transform) = world.get_local_transform_mut(entity) {
+                transform.scale = reveal.target_scale;
+            }
+            world.set_local_transform_dirty(entity, LocalTransformDirty);
+        }
+        self.reveal_timer = 0.0;
+    }
+
+    pub fn revealing_scale(&self, name: &str) -> Option<nalgebra_glm::Vec3> {
+        self.pending_reveals.iter()
+            .chain(&self.active_reveals)
+            .find(|reveal| reveal.name == name)
+            .map(|reveal| reveal.target_scale)
+    }
+
+    pub fn cancel_spawn_reveal(&mut self, name: &str) {
+        self.pending_reveals.retain(|reveal| reveal.name != name);
+        self.active_reveals.retain(|reveal| reveal.name != name);
+    }
+
+    pub fn select_entities(&self, names: &[String], prefix: Option<&str>) -> Vec<String> {
+        let prefix = prefix.map(|prefix| prefix.trim_end_matches('*'));
+        self.entities.keys()
+            .filter(|name| names.contains(*name) || prefix.is_some_and(|prefix| name.starts_with(prefix)))
+            .cloned()
+            .collect()
+    }
+
+    pub fn forget_entity(&mut self, name: &str) {
+        self.materials.remove(name);
+        self.highlights.remove(name);
+        self.emissive_pulses.remove(name);
+        self.orbits.remove(name);
+        self.velocities.remove(name);
+        self.billboards.remove(name);
+        self.keyframes.remove(name);
+        if self.camera_follow.as_ref().is_some_and(|follow| follow.target == name) {
+            self.camera_follow = None;
+        }
+        self.locked.remove(name);
+        self.shapes.remove(name);
+        self.gltf_sources.remove(name);
+    }
+
+    pub fn half_extents(&self, name: &str, transform: &LocalTransform) -> nalgebra_glm::Vec3 {
+        let base = self.shapes.get(name)
+            .map(|shape| shape_half_extents(shape))
+            .unwrap_or_else(|| shape_half_extents("cube"));
+        base.component_mul(&transform.scale).abs()
+    }
+
+    pub fn clear_grid_floor(&mut self, world: &mut World) {
+        for entity in self.grid_entities.drain(..) {
+            despawn_recursive_immediate(world, entity);
+        }
+    }
+
+    pub fn spawn_grid_floor(&mut self, world: &mut World, size: f32, spacing: f32) -> usize {
+        self.clear_grid_floor(world);
+
+        let half_lines = ((size * 0.5) / spacing).floor() as i32;
+        let half_lines = half_lines.min((MAX_GRID_LINES_PER_AXIS as i32 - 1) / 2);
+        let extent = size.min(MAX_GRID_LINES_PER_AXIS as f32 * spacing);
+
+        for index in -half_lines..=half_lines {
+            let offset = index as f32 * spacing;
+            let color = if index == 0 { GRID_AXIS } else { GRID_LINE };
+            let lines = [
+                (
+                    nalgebra_glm::Vec3::new(offset, 0.0, 0.0),
+                    nalgebra_glm::Vec3::new(GRID_LINE_THICKNESS, GRID_LINE_THICKNESS, extent),
+                ),
+                (
+                    nalgebra_glm::Vec3::new(0.0, 0.0, offset),
+                    nalgebra_glm::Vec3::new(extent, GRID_LINE_THICKNESS, GRID_LINE_THICKNESS),
+                ),
+            ];
+            for (position, scale) in lines {
+                let entity = spawn_cube_at(world, position);
+                if let Some(transform) = world.get_local_transform_mut(entity) {
+                    transform.scale = scale;
+                }
+                world.set_local_transform_dirty(entity, LocalTransformDirty);
+                let overrides = MaterialOverrides {
+                    color: Some(color),
+                    ..Default::default()
+                };
+                let material_name = if index == 0 { "grid_axis" } else { "grid_line" };
+                write_entity_material(world, entity, material_name, &overrides);
+                self.grid_entities.push(entity);
+            }
+        }
+
+        self.grid_entities.len()
+    }
+
+    pub fn apply_shadow_settings(&self, world: &mut World) {
+        let Some(sun) = self.sun_entity else {
+            return;
+        };
+        if let Some(light) = world.get_light_mut(sun) {
+            if let Some(enabled) = self.shadows_enabled {
+                light.cast_shadows = enabled;
+            }
+            if let Some(bias) = self.shadow_bias {
+                light.shadow_bias = bias;
+            }
+        }
+    }
+
+    pub fn bounds(&self, world: &World) -> Option<(nalgebra_glm::Vec3, nalgebra_glm::Vec3)> {
+        let mut bounds: Option<(nalgebra_glm::Vec3, nalgebra_glm::Vec3)> = None;
+        for (name, &entity) in &self.entities {
+            let Some(transform) = world.get_local_transform(entity) else {
+                continue;
+            };
+            let half_extents = self.half_extents(name, transform);
+            for corner in 0..8 {
+                let local = nalgebra_glm::Vec3::new(
+                    if corner & 1 == 0 { -1.0 } else { 1.0 },
+                    if corner & 2 == 0 { -1.0 } else { 1.0 },
+                    if corner & 4 == 0 { -1.0 } else { 1.0 },
+                )
+                .component_mul(&half_extents);
+                let point = transform.translation + nalgebra_glm::quat_rotate_vec3(&transform.rotation, &local);
+                bounds = Some(match bounds {
+                    Some((min, max)) => (nalgebra_glm::min2(&min, &point), nalgebra_glm::max2(&max, &point)),
+                    None => (point, point),
+                });
+            }
+        }
+        bounds
+    }
+
+    pub fn pick_entity(&self, world: &World, x: f32, y: f32) -> Option<String> {
+        let camera = self.camera_entity?;
+        let camera_transform = world.get_local_transform(camera)?;
+        let y_fov_rad = match world.get_camera(camera).map(|camera| &camera.projection) {
+            Some(Projection::Perspective(perspective)) => perspective.y_fov_rad,
+            _ => std::f32::consts::FRAC_PI_4,
+        };
+
+        let [width, height] = self.window_size;
+        if width == 0 || height == 0 {
+            return None;
+        }
+        let aspect_ratio = width as f32 / height as f32;
+        let ndc_x = 2.0 * x / width as f32 - 1.0;
+        let ndc_y = 1.0 - 2.0 * y / height as f32;
+        let half_fov_tan = (y_fov_rad * 0.5).tan();
+        let camera_direction = nalgebra_glm::normalize(&nalgebra_glm::Vec3::new(
+            ndc_x * half_fov_tan * aspect_ratio,
+            ndc_y * half_fov_tan,
+            -1.0,
+        ));
+        let origin = camera_transform.translation;
+        let direction = nalgebra_glm::quat_rotate_vec3(&camera_transform.rotation, &camera_direction);
+
+        let mut nearest: Option<(f32, &String)> = None;
+        for (name, &entity) in &self.entities {
+            let Some(transform) = world.get_local_transform(entity) else {
+                continue;
+            };
+            let half_extents = self.half_extents(name, transform);
+            let Some(distance) = ray_hits_oriented_box(&origin, &direction, transform, &half_extents) else {
+                continue;
+            };
+            if nearest.is_none_or(|(nearest_distance, _)| distance < nearest_distance) {
+                nearest = Some((distance, name));
+            }
+        }
+        nearest.map(|(_, name)| name.clone())
+    }
+}
+
+fn ray_hits_oriented_box(
+    origin: &nalgebra_glm::Vec3,
+    direction: &nalgebra_glm::Vec3,
+    transform: &LocalTransform,
+    half_extents: &nalgebra_glm::Vec3,
+) -> Option<f32> {
+    let inverse_rotation = nalgebra_glm::quat_inverse(&transform.rotation);
+    let local_origin = nalgebra_glm::quat_rotate_vec3(&inverse_rotation, &(origin - transform.translation));
+    let local_direction = nalgebra_glm::quat_rotate_vec3(&inverse_rotation, direction);
+
+    let mut near = f32::NEG_INFINITY;
+    let mut far = f32::INFINITY;
+    for axis in 0..3 {
+        let half_extent = half_extents[axis];
+        if local_direction[axis].abs() < f32::EPSILON {
+            if local_origin[axis].abs() > half_extent {
+                return None;
+            }
+            continue;
+        }
+        let first = (-half_extent - local_origin[axis]) / local_direction[axis];
+        let second = (half_extent - local_origin[axis]) / local_direction[axis];
+        near = near.max(first.min(second));
+        far = far.min(first.max(second));
+    }
+
+    if near > far || far < 0.0 {
+        return None;
+    }
+    Some(near.max(0.0))
+}
+
+pub const MAX_TEXTURE_DIMENSION: u32 = 4096;
+
+pub fn load_texture_file(path: &std::path::Path) -> Result<(Vec<u8>, u32, u32), String> {
+    let format = image::ImageFormat::from_path(path)
+        .map_err(|_| format!("unsupported texture '{}': use a .png, .jpg, or .jpeg file", path.display()))?;
+    if !matches!(format, image::ImageFormat::Png | image::ImageFormat::Jpeg) {
+        return Err(format!("unsupported texture '{}': use a .png, .jpg, or .jpeg file", path.display()));
+    }
+    let decoded = image::open(path).map_err(|error| format!("failed to load texture '{}': {error}", path.display()))?;
+    let (width, height) = (decoded.width(), decoded.height());
+    if width > MAX_TEXTURE_DIMENSION || height > MAX_TEXTURE_DIMENSION {
+        return Err(format!(
+            "texture '{}' is {width}x{height}; the maximum is {MAX_TEXTURE_DIMENSION}x{MAX_TEXTURE_DIMENSION}",
+            path.display()
+        ));
+    }
+    Ok((decoded.to_rgba8().into_raw(), width, height))
+}
+
+pub fn euler_degrees_to_quat(rotation: [f32; 3]) -> nalgebra_glm::Quat {
+    nalgebra_glm::quat_angle_axis(rotation[2].to_radians(), &nalgebra_glm::Vec3::new(0.0, 0.0, 1.0))
+        * nalgebra_glm::quat_angle_axis(rotation[1].to_radians(), &nalgebra_glm::Vec3::new(0.0, 1.0, 0.0))
+        * nalgebra_glm::quat_angle_axis(rotation[0].to_radians(), &nalgebra_glm::Vec3::new(1.0, 0.0, 0.0))
+}
+
+pub fn describe_offset(offset: nalgebra_glm::Vec3) -> String {
+    let axes = [(offset.x, "right", "left"), (offset.y, "up", "down"), (offset.z, "back", "forward")];
+    let moved: Vec<_> = axes.iter().filter(|(amount, _, _)| amount.abs() > f32::EPSILON).collect();
+    match moved.as_slice() {
+        [] => "nowhere".to_string(),
+        [(amount, positive, negative)] => {
+            let direction = if *amount > 0.0 { positive } else { negative };
+            format!("{direction} {} units", amount.abs())
+        }
+        _ => format!("by [{}, {}, {}]", offset.x, offset.y, offset.z),
+    }
+}
+
+pub fn compose_transform_matrix(transform: &LocalTransform) -> [f32; 16] {
+    let matrix = nalgebra_glm::translation(&transform.translation)
+        * nalgebra_glm::quat_to_mat4(&transform.rotation)
+        * nalgebra_glm::scaling(&transform.scale);
+    let mut elements = [0.0; 16];
+    elements.copy_from_slice(matrix.as_slice());
+    elements
+}
+
+const MAX_AXIS_SKEW_COSINE: f32 = 1e-3;
+
+pub fn decompose_transform_matrix(
+    elements: &[f32; 16],
+) -> Result<(nalgebra_glm::Vec3, nalgebra_glm::Quat, nalgebra_glm::Vec3), String> {
+    let matrix = nalgebra_glm::make_mat4(elements);
+    let determinant = matrix.determinant();
+    if !determinant.is_finite() || determinant.abs() < 1e-6 {
+        return Err("matrix is not invertible".to_string());
+    }
+
+    let translation = nalgebra_glm::Vec3::new(matrix[(0, 3)], matrix[(1, 3)], matrix[(2, 3)]);
+    let mut axis_x = nalgebra_glm::Vec3::new(matrix[(0, 0)], matrix[(1, 0)], matrix[(2, 0)]);
+    let axis_y = nalgebra_glm::Vec3::new(matrix[(0, 1)], matrix[(1, 1)], matrix[(2, 1)]);
+    let axis_z = nalgebra_glm::Vec3::new(matrix[(0, 2)], matrix[(1, 2)], matrix[(2, 2)]);
+
+    for ((first_name, first), (second_name, second)) in [
+        (("x", axis_x), ("y", axis_y)),
+        (("x", axis_x), ("z", axis_z)),
+        (("y", axis_y), ("z", axis_z)),
+    ] {
+        let cosine = first.normalize().dot(&second.normalize());
+        if cosine.abs() > MAX_AXIS_SKEW_COSINE {
+            let angle = cosine.clamp(-1.0, 1.0).acos().to_degrees();
+            return Err(format!(
+                "matrix contains skew: the {first_name} and {second_name} axes are {angle:.2} degrees apart instead of 90, which translation, rotation, and scale cannot represent"
+            ));
+        }
+    }
+
+    let mut scale = nalgebra_glm::Vec3::new(axis_x.norm(), axis_y.norm(), axis_z.norm());
+    if determinant < 0.0 {
+        scale.x = -scale.x;
+        axis_x = -axis_x;
+    }
+
+    let rotation_matrix = nalgebra_glm::Mat3::from_columns(&[
+        axis_x / scale.x.abs(),
+        axis_y / scale.y,
+        axis_z / scale.z,
+    ]);
+    let rotation = nalgebra_glm::quat_normalize(&nalgebra_glm::mat3_to_quat(&rotation_matrix));
+
+    Ok((translation, rotation, scale))
+}
+
+fn srgb_to_linear(channel: f32) -> f32 {
+    if channel <= 0.04045 {
+        channel / 12.92
+    } else {
+        ((channel + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+pub fn build_material(overrides: &MaterialOverrides) -> Material {
+    let mut material = Material::default();
+    if let Some(color) = overrides.color {
+        material.base_color = [srgb_to_linear(color[0]), srgb_to_linear(color[1]), srgb_to_linear(color[2]), color[3]];
+        if color[3] < 1.0 {
+            material.alpha_mode = AlphaMode::Blend;
+        }
+    }
+    if let Some(roughness) = overrides.roughness {
+        material.roughness = roughness.clamp(0.0, 1.0);
+    }
+    if let Some(metallic) = overrides.metallic {
+        material.metallic = metallic.clamp(0.0, 1.0);
+    }
+    if let Some(emissive) = overrides.emissive {
+        material.emissive_factor = emissive;
+    }
+    if let Some(texture) = &overrides.texture {
+        material.base_texture = Some(texture.clone());
+    }
+    material
+}
+
+pub fn write_entity_material(world: &mut World, entity: Entity, name: &str, overrides: &MaterialOverrides) {
+    let material_name = format!("watchtower_{name}");
+    material_registry_insert(&mut world.resources.material_registry, material_name.clone(), build_material(overrides));
+    world.set_material_ref(entity, MaterialRef::new(material_name));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scaled_sphere_world_size() {
+        let size = shape_half_extents("sphere").component_mul(&nalgebra_glm::vec3(2.0, 3.0, 4.0)) * 2.0;
+        assert_eq!(size, nalgebra_glm::vec3(2.0, 3.0, 4.0));
+    }
+
+    #[test]
+    fn scaled_torus_world_size() {
+        let size = shape_half_extents("torus").component_mul(&nalgebra_glm::vec3(2.0, 2.0, 2.0)) * 2.0;
+        assert_eq!(size, nalgebra_glm::vec3(3.0, 1.0, 3.0));
+    }
+
+    #[test]
+    fn srgb_to_linear_converts_mid_gray_and_ends() {
+        assert!((srgb_to_linear(0.5) - 0.2140).abs() < 1e-4);
+        assert_eq!(srgb_to_linear(0.0), 0.0);
+        assert!((srgb_to_linear(1.0) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn translucent_color_builds_blended_material() {
+        let translucent = MaterialOverrides {
+            color: Some([1.0, 1.0, 1.0, 0.5]),
+            ..Default::default()
+        };
+        assert!(matches!(build_material(&translucent).alpha_mode, AlphaMode::Blend));
+
+        let opaque = MaterialOverrides {
+            color: Some([1.0, 1.0, 1.0, 1.0]),
+            ..Default::default()
+        };
+        assert!(!matches!(build_material(&opaque).alpha_mode, AlphaMode::Blend));
+    }
 }
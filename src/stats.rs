@@ -0,0 +1,147 @@
+use std::collections::VecDeque;
+
+pub const MAX_FRAME_STAT_SAMPLES: usize = 120;
+
+#[derive(Clone, serde::Serialize)]
+pub struct FrameStatSample {
+    pub second: u64,
+    pub frames: u32,
+    pub average_frame_ms: f32,
+    pub max_frame_ms: f32,
+    pub entity_count: usize,
+    pub spawns: u32,
+}
+
+#[derive(Default)]
+pub struct FrameStats {
+    samples: VecDeque<FrameStatSample>,
+    elapsed_seconds: u64,
+    window_seconds: f32,
+    window_frames: u32,
+    window_max_seconds: f32,
+    window_spawns: u32,
+}
+
+impl FrameStats {
+    pub fn record_spawn(&mut self) {
+        self.window_spawns += 1;
+    }
+
+    pub fn record_frame(&mut self, delta_seconds: f32, entity_count: usize) {
+        self.window_seconds += delta_seconds;
+        self.window_frames += 1;
+        self.window_max_seconds = self.window_max_seconds.max(delta_seconds);
+        if self.window_seconds < 1.0 {
+            return;
+        }
+
+        self.elapsed_seconds += 1;
+        if self.samples.len() == MAX_FRAME_STAT_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(FrameStatSample {
+            second: self.elapsed_seconds,
+            frames: self.window_frames,
+            average_frame_ms: self.window_seconds * 1000.0 / self.window_frames as f32,
+            max_frame_ms: self.window_max_seconds * 1000.0,
+            entity_count,
+            spawns: self.window_spawns,
+        });
+
+        self.window_seconds = 0.0;
+        self.window_frames = 0;
+        self.window_max_seconds = 0.0;
+        self.window_spawns = 0;
+    }
+
+    pub fn recent(&self, count: usize) -> Vec<FrameStatSample> {
+        let skip = self.samples.len().saturating_sub(count);
+        self.samples.iter().skip(skip).cloned().collect()
+    }
+}
+
+pub struct SceneMetrics {
+    pub entity_count: usize,
+    pub animated_count: usize,
+    pub grid_line_count: usize,
+    pub shadows_enabled: bool,
+}
+
+const TARGET_FRAME_MS: f32 = 1000.0 / 30.0;
+const LARGE_SCENE_ENTITIES: usize = 500;
+const HIGH_SPAWN_RATE: f32 = 20.0;
+const LARGE_GRID_LINES: usize = 200;
+const MANY_ANIMATED_ENTITIES: usize = 100;
+const RECENT_WINDOW: usize = 10;
+
+fn average(samples: &[FrameStatSample], value: impl Fn(&FrameStatSample) -> f32) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    samples.iter().map(value).sum::<f32>() / samples.len() as f32
+}
+
+pub fn performance_recommendations(samples: &[FrameStatSample], metrics: &SceneMetrics) -> Vec<String> {
+    let mut recommendations = Vec::new();
+    let recent = &samples[samples.len().saturating_sub(RECENT_WINDOW)..];
+    let earliest = &samples[..samples.len().min(RECENT_WINDOW)];
+
+    let recent_frame_ms = average(recent, |sample| sample.average_frame_ms);
+    if recent_frame_ms > TARGET_FRAME_MS {
+        recommendations.push(format!(
+            "Frames average {recent_frame_ms:.1} ms (~{:.0} FPS) over the last {} seconds, below the 30 FPS target.",
+            1000.0 / recent_frame_ms,
+            recent.len()
+        ));
+        if metrics.shadows_enabled {
+            recommendations.push("Shadows are enabled; try set_shadows with enabled=false to see if frame time recovers.".to_string());
+        }
+    }
+
+    if samples.len() >= RECENT_WINDOW * 2 {
+        let earliest_frame_ms = average(earliest, |sample| sample.average_frame_ms);
+        if earliest_frame_ms > 0.0 && recent_frame_ms > earliest_frame_ms * 1.5 {
+            recommendations.push(format!(
+                "Frame time rose from {earliest_frame_ms:.1} ms to {recent_frame_ms:.1} ms over the sampled period; the scene is getting slower over time."
+            ));
+        }
+
+        let earliest_entities = average(earliest, |sample| sample.entity_count as f32);
+        let recent_entities = average(recent, |sample| sample.entity_count as f32);
+        if recent_entities > earliest_entities * 1.5 && recent_entities - earliest_entities > 50.0 {
+            recommendations.push(format!(
+                "Entity count grew from {earliest_entities:.0} to {recent_entities:.0}; check for entities that are spawned but never removed."
+            ));
+        }
+    }
+
+    let spawn_rate = average(recent, |sample| sample.spawns as f32);
+    if spawn_rate > HIGH_SPAWN_RATE {
+        recommendations.push(format!(
+            "Spawning about {spawn_rate:.0} entities per second; reuse or remove entities instead of spawning continuously."
+        ));
+    }
+
+    if metrics.entity_count > LARGE_SCENE_ENTITIES {
+        recommendations.push(format!(
+            "The scene has {} named entities; reduce large grids or remove entities that are off screen.",
+            metrics.entity_count
+        ));
+    }
+
+    if metrics.grid_line_count > LARGE_GRID_LINES {
+        recommendations.push(format!(
+            "The grid floor uses {} line entities; increase its spacing or hide it with set_grid_floor.",
+            metrics.grid_line_count
+        ));
+    }
+
+    if metrics.animated_count > MANY_ANIMATED_ENTITIES {
+        recommendations.push(format!(
+            "{} entities have per-frame effects (highlights, pulses, orbits, velocities, or billboards); each one updates every frame.",
+            metrics.animated_count
+        ));
+    }
+
+    recommendations
+}